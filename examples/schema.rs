@@ -4,9 +4,14 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use pegasus::msg::{
-    ExecuteMsg, InstantiateMsg, OfferResponse, OffersResponse, ParamsResponse, QueryMsg,
+    AdminResponse, BlockedSendersResponse, CollectionParamsResponse, CollectionStatsResponse,
+    ConfigResponse, DepositsResponse, ExecuteMsg, FilteredOffersResponse, InboxPreferencesResponse,
+    InstantiateMsg, OfferHookExecuteMsg, OfferResponse, OfferStatusResponse, OffersHealthResponse,
+    OffersResponse, ParamsResponse, QueryMsg, ReceiveNftMsg, ReputationResponse,
+    SimulateAcceptResponse, StatsResponse, TokenInterestResponse, UserStatsResponse,
 };
 use pegasus::state::SudoParams;
+use sg_controllers::HooksResponse;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -17,8 +22,26 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveNftMsg), &out_dir);
     export_schema(&schema_for!(OfferResponse), &out_dir);
     export_schema(&schema_for!(OffersResponse), &out_dir);
     export_schema(&schema_for!(ParamsResponse), &out_dir);
     export_schema(&schema_for!(SudoParams), &out_dir);
+    export_schema(&schema_for!(UserStatsResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(CollectionStatsResponse), &out_dir);
+    export_schema(&schema_for!(ReputationResponse), &out_dir);
+    export_schema(&schema_for!(TokenInterestResponse), &out_dir);
+    export_schema(&schema_for!(InboxPreferencesResponse), &out_dir);
+    export_schema(&schema_for!(BlockedSendersResponse), &out_dir);
+    export_schema(&schema_for!(DepositsResponse), &out_dir);
+    export_schema(&schema_for!(HooksResponse), &out_dir);
+    export_schema(&schema_for!(OfferHookExecuteMsg), &out_dir);
+    export_schema(&schema_for!(SimulateAcceptResponse), &out_dir);
+    export_schema(&schema_for!(OfferStatusResponse), &out_dir);
+    export_schema(&schema_for!(OffersHealthResponse), &out_dir);
+    export_schema(&schema_for!(AdminResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(CollectionParamsResponse), &out_dir);
+    export_schema(&schema_for!(FilteredOffersResponse), &out_dir);
 }
@@ -1,20 +1,34 @@
 use crate::error::ContractError;
 use crate::execute::{
-    execute_accept_offer, execute_create_offer, execute_reject_offer, execute_remove_offer,
-    execute_remove_stale_offer,
+    execute_accept_admin, execute_accept_offer, execute_block_sender,
+    execute_create_escrowed_offer, execute_create_offer, execute_propose_admin,
+    execute_rebuild_offers, execute_receive_nft, execute_reject_blocked_offers,
+    execute_reject_offer, execute_remove_offer, execute_remove_stale_offer,
+    execute_set_inbox_preferences, execute_unblock_sender, execute_update_admin_params,
+    execute_withdraw_deposits,
 };
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
-use crate::query::{query_offer, query_offers_by_peer, query_offers_by_sender, query_params};
-use crate::state::{SudoParams, MAX_EXPIRY, MIN_EXPIRY, SUDO_PARAMS};
-use crate::sudo::{sudo_update_params, ParamInfo};
-use crate::ExpiryRangeError;
+use crate::query::{
+    query_admin, query_blocked_senders, query_collection_params, query_collection_stats,
+    query_config, query_deposits, query_fillable_offers, query_inbox_preferences, query_offer,
+    query_offer_hooks, query_offer_status, query_offers, query_offers_by_collection,
+    query_offers_by_peer, query_offers_by_sender, query_offers_health, query_params,
+    query_reputation, query_simulate_accept, query_stats, query_token_interest, query_user_stats,
+};
+use crate::reply::{reply_offer_hook, reply_transfer, OFFER_HOOK_REPLY_ID, TRANSFER_REPLY_ID};
+use crate::state::{CollectionParams, SudoParams, Token, ADMIN, OFFERS_REBUILD, SUDO_PARAMS};
+use crate::sudo::{
+    sudo_add_offer_hook, sudo_remove_collection_params, sudo_remove_offer_hook,
+    sudo_set_collection_params, sudo_update_admin, sudo_update_params, validate_expiry_range,
+    validate_limits, ParamInfo,
+};
 
 // use crate::query::{query_offers_by_sender};
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, StdError, StdResult,
+    to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, StdError, StdResult,
 };
 use cw2::set_contract_version;
 use semver::Version;
@@ -33,27 +47,23 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    msg.offer_expiry.validate()?;
-
-    if msg.offer_expiry.min < MIN_EXPIRY {
-        return Err(ContractError::ExpiryRange(
-            ExpiryRangeError::InvalidExpirationRange {},
-        ));
-    }
-    if msg.offer_expiry.max > MAX_EXPIRY {
-        return Err(ContractError::ExpiryRange(
-            ExpiryRangeError::InvalidExpirationRange {},
-        ));
-    }
+    validate_expiry_range(&msg.offer_expiry)?;
 
     let params = SudoParams {
         offer_expiry: msg.offer_expiry,
         maintainer: deps.api.addr_validate(&msg.maintainer)?,
         max_offers: msg.max_offers,
+        max_incoming_offers: msg.max_incoming_offers,
         bundle_limit: msg.bundle_limit,
+        max_memo_length: msg.max_memo_length,
     };
+    validate_limits(&params)?;
     SUDO_PARAMS.save(deps.storage, &params)?;
 
+    if let Some(admin) = msg.admin {
+        ADMIN.save(deps.storage, &deps.api.addr_validate(&admin)?)?;
+    }
+
     Ok(Response::new())
 }
 
@@ -66,12 +76,20 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     let api = deps.api;
 
+    // offers stored by an older version have to be rebuilt before they can be handled
+    if !matches!(msg, ExecuteMsg::RebuildOffers { .. })
+        && OFFERS_REBUILD.may_load(deps.storage)?.is_some()
+    {
+        return Err(ContractError::RebuildPending {});
+    }
+
     match msg {
         ExecuteMsg::CreateOffer {
             offered_nfts,
             wanted_nfts,
             peer,
             expires_at,
+            memo,
         } => execute_create_offer(
             deps,
             env,
@@ -80,17 +98,66 @@ pub fn execute(
             wanted_nfts,
             api.addr_validate(&peer)?,
             expires_at,
+            memo,
         ),
 
         ExecuteMsg::RemoveOffer { id } => execute_remove_offer(deps, info, id),
         ExecuteMsg::AcceptOffer { id } => execute_accept_offer(deps, env, info, id),
         ExecuteMsg::RejectOffer { id } => execute_reject_offer(deps, info, id),
+        ExecuteMsg::ReceiveNft(msg) => execute_receive_nft(deps, info, msg),
+        ExecuteMsg::CreateEscrowedOffer {
+            offered_nfts,
+            wanted_nfts,
+            peer,
+            expires_at,
+            memo,
+        } => execute_create_escrowed_offer(
+            deps,
+            env,
+            info,
+            offered_nfts,
+            wanted_nfts,
+            api.addr_validate(&peer)?,
+            expires_at,
+            memo,
+        ),
+        ExecuteMsg::WithdrawDeposits { nfts } => execute_withdraw_deposits(deps, info, nfts),
         ExecuteMsg::RemoveStaleOffer { id } => execute_remove_stale_offer(deps, env, info, id),
+        ExecuteMsg::SetInboxPreferences {
+            allowed_senders,
+            min_offered_nfts,
+        } => execute_set_inbox_preferences(deps, info, allowed_senders, min_offered_nfts),
+        ExecuteMsg::BlockSender { sender } => {
+            execute_block_sender(deps, info, api.addr_validate(&sender)?)
+        }
+        ExecuteMsg::UnblockSender { sender } => {
+            execute_unblock_sender(deps, info, api.addr_validate(&sender)?)
+        }
+        ExecuteMsg::RejectBlockedOffers { sender } => {
+            execute_reject_blocked_offers(deps, info, api.addr_validate(&sender)?)
+        }
+        ExecuteMsg::UpdateAdminParams {
+            max_offers,
+            max_incoming_offers,
+            bundle_limit,
+        } => execute_update_admin_params(
+            deps,
+            env,
+            info,
+            max_offers,
+            max_incoming_offers,
+            bundle_limit,
+        ),
+        ExecuteMsg::ProposeAdmin { admin } => {
+            execute_propose_admin(deps, info, api.addr_validate(&admin)?)
+        }
+        ExecuteMsg::AcceptAdmin {} => execute_accept_admin(deps, info),
+        ExecuteMsg::RebuildOffers { limit } => execute_rebuild_offers(deps, limit),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let api = deps.api;
 
     match msg {
@@ -101,6 +168,97 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::OffersByPeer { peer } => {
             to_binary(&query_offers_by_peer(deps, api.addr_validate(&peer)?)?)
         }
+        QueryMsg::UserStats { address } => {
+            to_binary(&query_user_stats(deps, api.addr_validate(&address)?)?)
+        }
+        QueryMsg::Stats {} => to_binary(&query_stats(deps)?),
+        QueryMsg::CollectionStats { collection } => to_binary(&query_collection_stats(
+            deps,
+            api.addr_validate(&collection)?,
+        )?),
+        QueryMsg::TokenInterest {
+            collection,
+            token_id,
+        } => to_binary(&query_token_interest(
+            deps,
+            env,
+            api.addr_validate(&collection)?,
+            token_id,
+        )?),
+        QueryMsg::FillableOffers {
+            owner,
+            collection,
+            start_after,
+            limit,
+        } => to_binary(&query_fillable_offers(
+            deps,
+            env,
+            api.addr_validate(&owner)?,
+            api.addr_validate(&collection)?,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Reputation { address } => {
+            to_binary(&query_reputation(deps, api.addr_validate(&address)?)?)
+        }
+        QueryMsg::InboxPreferences { address } => to_binary(&query_inbox_preferences(
+            deps,
+            api.addr_validate(&address)?,
+        )?),
+        QueryMsg::Deposits {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_deposits(
+            deps,
+            api.addr_validate(&owner)?,
+            start_after
+                .map(|token| -> StdResult<_> {
+                    Ok(Token {
+                        collection: api.addr_validate(&token.collection)?,
+                        token_id: token.token_id,
+                    })
+                })
+                .transpose()?,
+            limit,
+        )?),
+        QueryMsg::BlockedSenders { owner } => {
+            to_binary(&query_blocked_senders(deps, api.addr_validate(&owner)?)?)
+        }
+        QueryMsg::OfferHooks {} => to_binary(&query_offer_hooks(deps)?),
+        QueryMsg::Admin {} => to_binary(&query_admin(deps)?),
+        QueryMsg::SimulateAccept { id, accepter } => to_binary(&query_simulate_accept(
+            deps,
+            env,
+            id,
+            api.addr_validate(&accepter)?,
+        )?),
+        QueryMsg::OfferStatus { id } => to_binary(&query_offer_status(deps, env, id)?),
+        QueryMsg::OffersHealth { start_after, limit } => {
+            to_binary(&query_offers_health(deps, env, start_after, limit)?)
+        }
+        QueryMsg::OffersByCollection {
+            collection,
+            side,
+            start_after,
+            limit,
+        } => to_binary(&query_offers_by_collection(
+            deps,
+            api.addr_validate(&collection)?,
+            side,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Offers {
+            filter,
+            start_after,
+            limit,
+        } => to_binary(&query_offers(deps, env, filter, start_after, limit)?),
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::CollectionParams { collection } => to_binary(&query_collection_params(
+            deps,
+            api.addr_validate(&collection)?,
+        )?),
         QueryMsg::Params {} => to_binary(&query_params(deps)?),
     }
 }
@@ -124,21 +282,36 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
     // set the new version
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    // do any desired state migrations...
+    // offers stored before 1.1.0 are missing from the newer indexes and the open offer
+    // counts, which didn't exist yet. They are rebuilt in batches with `RebuildOffers`.
+    if version < Version::new(1, 1, 0) {
+        OFFERS_REBUILD.save(deps.storage, &0)?;
+    }
 
     Ok(Response::default())
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        OFFER_HOOK_REPLY_ID => reply_offer_hook(deps, env, msg),
+        id if id >= TRANSFER_REPLY_ID => reply_transfer(deps, env, msg),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
-    // let api = deps.api;
+    let api = deps.api;
 
     match msg {
         SudoMsg::UpdateParams {
             offer_expiry,
             maintainer,
             max_offers,
+            max_incoming_offers,
             bundle_limit,
+            max_memo_length,
         } => sudo_update_params(
             deps,
             env,
@@ -146,8 +319,34 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractE
                 offer_expiry,
                 maintainer,
                 max_offers,
+                max_incoming_offers,
                 bundle_limit,
+                max_memo_length,
             },
         ),
+        SudoMsg::UpdateAdmin { admin } => {
+            let admin = admin.map(|a| api.addr_validate(&a)).transpose()?;
+            sudo_update_admin(deps, env, admin)
+        }
+        SudoMsg::SetCollectionParams {
+            collection,
+            max_tokens,
+            offer_expiry,
+        } => sudo_set_collection_params(
+            deps,
+            env,
+            api.addr_validate(&collection)?,
+            CollectionParams {
+                max_tokens,
+                offer_expiry,
+            },
+        ),
+        SudoMsg::RemoveCollectionParams { collection } => {
+            sudo_remove_collection_params(deps, env, api.addr_validate(&collection)?)
+        }
+        SudoMsg::AddOfferHook { hook } => sudo_add_offer_hook(deps, env, api.addr_validate(&hook)?),
+        SudoMsg::RemoveOfferHook { hook } => {
+            sudo_remove_offer_hook(deps, env, api.addr_validate(&hook)?)
+        }
     }
 }
@@ -1,7 +1,8 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp};
 use thiserror::Error;
 
 use crate::helpers::ExpiryRangeError;
+use sg_controllers::HookError;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -14,6 +15,9 @@ pub enum ContractError {
     #[error("Invalid collection address")]
     InvalidCollection {},
 
+    #[error("Invalid token id {token_id:?}")]
+    InvalidTokenId { token_id: String },
+
     #[error("UnauthorizedOwner")]
     UnauthorizedSender {},
 
@@ -45,9 +49,115 @@ pub enum ContractError {
     #[error("Address {addr:?} cannot create more than {max_offers:?} offers")]
     MaxOffers { addr: String, max_offers: u64 },
 
+    #[error("Address {addr:?} cannot receive more than {max_offers:?} offers")]
+    MaxIncomingOffers { addr: String, max_offers: u64 },
+
+    #[error("Address {peer:?} doesn't accept offers from {sender:?}")]
+    SenderNotAccepted { sender: String, peer: String },
+
+    #[error("Address {sender:?} is not blocked")]
+    SenderNotBlocked { sender: String },
+
+    #[error("Cant block own address")]
+    CannotBlockSelf {},
+
+    #[error("Address {peer:?} only accepts offers of at least {min:?} NFTs")]
+    MinOfferedNfts { peer: String, min: u64 },
+
+    #[error("Max offers {max_offers:?} is out of bounds ({min:?} - {max:?})")]
+    InvalidMaxOffers { max_offers: u64, min: u64, max: u64 },
+
+    #[error("Bundle limit {bundle_limit:?} is out of bounds ({min:?} - {max:?})")]
+    InvalidBundleLimit {
+        bundle_limit: u64,
+        min: u64,
+        max: u64,
+    },
+
+    #[error("Max incoming offers {max_incoming_offers:?} is out of bounds ({min:?} - {max:?})")]
+    InvalidMaxIncomingOffers {
+        max_incoming_offers: u64,
+        min: u64,
+        max: u64,
+    },
+
+    #[error("Max memo length {max_memo_length:?} is out of bounds ({min:?} - {max:?})")]
+    InvalidMaxMemoLength {
+        max_memo_length: u64,
+        min: u64,
+        max: u64,
+    },
+
+    #[error("Token (collection: {collection:?}, id: {token_id:?}) is not deposited by the sender")]
+    NotDeposited { collection: String, token_id: u32 },
+
+    #[error("Offers cannot contain more than {limit:?} NFTs of collection {collection:?}")]
+    MaxCollectionTokens { collection: String, limit: u64 },
+
+    #[error("Token (collection: {collection:?}, id: {token_id:?}) is in the offer more than once")]
+    DuplicateToken { collection: String, token_id: u32 },
+
     #[error("Bundle size cannot exceed {limit:?}")]
     MaxBundle { limit: u64 },
 
+    #[error(
+        "Approval of Token (collection: {collection:?}, id: {token_id:?}) expires at {approval_expires:?}, before the offer expires at {offer_expires:?}"
+    )]
+    ApprovalExpiresBeforeOffer {
+        collection: String,
+        token_id: u32,
+        approval_expires: Timestamp,
+        offer_expires: Timestamp,
+    },
+
+    #[error(
+        "Approval of Token (collection: {collection:?}, id: {token_id:?}) expires at block {height:?}, which can't be checked against the offer expiry"
+    )]
+    ApprovalExpiresAtHeight {
+        collection: String,
+        token_id: u32,
+        height: u64,
+    },
+
+    #[error("Memo cannot be longer than {max:?} bytes")]
+    MemoTooLong { max: u64 },
+
+    #[error("Caller is not the admin")]
+    NotAdmin {},
+
+    #[error("Caller is not the pending admin")]
+    NotPendingAdmin {},
+
+    #[error("Offer {id:?} has expired")]
+    OfferExpired { id: u64 },
+
+    #[error("Offer {id:?} has not expired yet")]
+    OfferNotExpired { id: u64 },
+
     #[error("{0}")]
     ExpiryRange(#[from] ExpiryRangeError),
+
+    #[error("{0}")]
+    Hook(#[from] HookError),
+
+    #[error("Transfer of Token (collection: {collection:?}, id: {token_id:?}) failed")]
+    TransferFailed { collection: String, token_id: u32 },
+
+    #[error(
+        "Token (collection: {collection:?}, id: {token_id:?}) was not transferred to {recipient:?}"
+    )]
+    OwnershipNotTransferred {
+        collection: String,
+        token_id: u32,
+        recipient: String,
+    },
+
+    #[error("Unknown reply id {id:?}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Offers stored before the migration are still being rebuilt")]
+    RebuildPending {},
+
+    #[error("There are no offers to rebuild")]
+    NoRebuildPending {},
 }
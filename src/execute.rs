@@ -1,14 +1,32 @@
 use crate::error::ContractError;
-use crate::msg::TokenMsg;
-use crate::query::query_offers_by_sender;
-use crate::state::{next_offer_id, offers, Offer, Token, SUDO_PARAMS};
+use crate::helpers::{approval_expiration, check_accept};
+use crate::msg::{AcceptIssue, HookAction, OfferHookMsg, ReceiveNftMsg, TokenMsg};
+use crate::query::query_offers_by_peer;
+use crate::reply::{OFFER_HOOK_REPLY_ID, TRANSFER_REPLY_ID};
+use crate::state::{
+    add_offer_to_stats, add_trade_to_collection_stats, deposits, next_offer_id, offers,
+    rebuild_offers, remove_offer_from_stats, return_deposits, update_trade_history,
+    update_trade_stats, InboxPreferences, Offer, Token, TokenId, Transfer, ADMIN, BLOCKED_SENDERS,
+    COLLECTION_PARAMS, INBOX_PREFERENCES, OFFERS_REBUILD, OFFER_HOOKS, PENDING_ADMIN,
+    PENDING_TRANSFERS, SUDO_PARAMS, USER_STATS,
+};
+use crate::sudo::{sudo_update_params, ParamInfo};
 // use crate::query::{query_offers_by_sender};
 
-use cosmwasm_std::{to_binary, Addr, Deps, DepsMut, Env, MessageInfo, SubMsg, Timestamp, WasmMsg};
-use cw721::{Cw721ExecuteMsg, OwnerOfResponse};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, Api, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order,
+    StdResult, Storage, Timestamp, WasmMsg,
+};
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg, OwnerOfResponse};
 use cw721_base::helpers::Cw721Contract;
-use sg_std::Response;
+use cw_utils::Expiration;
+use sg_std::{Response, SubMsg};
+use std::collections::BTreeSet;
 
+const DEFAULT_REBUILD_LIMIT: u32 = 50;
+const MAX_REBUILD_LIMIT: u32 = 100;
+
+#[allow(clippy::too_many_arguments)]
 pub fn execute_create_offer(
     deps: DepsMut,
     env: Env,
@@ -17,61 +35,268 @@ pub fn execute_create_offer(
     wanted_tokens: Vec<TokenMsg>,
     peer: Addr,
     expires_at: Option<Timestamp>,
+    memo: Option<String>,
 ) -> Result<Response, ContractError> {
-    if info.sender == peer {
-        return Err(ContractError::AlreadyOwned {});
+    create_offer(
+        deps,
+        env,
+        info.sender,
+        false,
+        offered_tokens,
+        wanted_tokens,
+        peer,
+        expires_at,
+        memo,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_escrowed_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offered_tokens: Vec<TokenMsg>,
+    wanted_tokens: Vec<TokenMsg>,
+    peer: Addr,
+    expires_at: Option<Timestamp>,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    create_offer(
+        deps,
+        env,
+        info.sender,
+        true,
+        offered_tokens,
+        wanted_tokens,
+        peer,
+        expires_at,
+        memo,
+    )
+}
+
+/// Handles NFTs sent to the contract with cw721 `SendNft` by depositing them
+pub fn execute_receive_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    wrapper: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // the calling contract is the collection of the received token, and the only
+    // authority on who sent it
+    let collection = info.sender;
+    let token_id: TokenId =
+        wrapper
+            .token_id
+            .parse()
+            .map_err(|_| ContractError::InvalidTokenId {
+                token_id: wrapper.token_id.clone(),
+            })?;
+    let owner = deps.api.addr_validate(&wrapper.sender)?;
+
+    match from_binary(&wrapper.msg)? {
+        ReceiveNftMsg::Deposit {} => {
+            deposits().save(deps.storage, (collection.clone(), token_id), &owner)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "deposit_nft")
+                .add_attribute("collection", collection)
+                .add_attribute("token_id", token_id.to_string())
+                .add_attribute("owner", owner))
+        }
     }
+}
 
-    if offered_tokens.is_empty() {
+pub fn execute_withdraw_deposits(
+    deps: DepsMut,
+    info: MessageInfo,
+    tokens: Vec<TokenMsg>,
+) -> Result<Response, ContractError> {
+    if tokens.is_empty() {
         return Err(ContractError::EmptyTokenVector {});
     }
-    if wanted_tokens.is_empty() {
+
+    let mut withdrawn: Vec<Token> = vec![];
+    for token in tokens {
+        let token = Token {
+            collection: deps.api.addr_validate(&token.collection)?,
+            token_id: token.token_id,
+        };
+        only_depositor(deps.as_ref(), &info.sender, &token)?;
+        deposits().remove(deps.storage, (token.collection.clone(), token.token_id))?;
+        withdrawn.push(token);
+    }
+
+    let mut res = Response::new();
+    transfer_nfts(
+        deps.storage,
+        transfers_to(&info.sender, &withdrawn),
+        &mut res,
+    )?;
+
+    Ok(res
+        .add_attribute("action", "withdraw_deposits")
+        .add_attribute("owner", info.sender)
+        .add_attribute("withdrawn", withdrawn.len().to_string()))
+}
+
+/// Creates an offer from `sender`. The offered NFTs of an `escrowed` offer have to be
+/// deposited by the sender, they are held by the contract until the offer closes.
+#[allow(clippy::too_many_arguments)]
+fn create_offer(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    escrowed: bool,
+    offered_tokens: Vec<TokenMsg>,
+    wanted_tokens: Vec<TokenMsg>,
+    peer: Addr,
+    expires_at: Option<Timestamp>,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    if sender == peer {
+        return Err(ContractError::AlreadyOwned {});
+    }
+
+    if offered_tokens.is_empty() || wanted_tokens.is_empty() {
         return Err(ContractError::EmptyTokenVector {});
     }
 
-    let offers_from_sender = query_offers_by_sender(deps.as_ref(), info.sender.clone())?;
+    // validate the tokens, no token can be in the offer twice
+    let (offered_tokens, wanted_tokens) =
+        normalize_tokens(deps.api, offered_tokens, wanted_tokens)?;
+
     let params = SUDO_PARAMS.load(deps.storage)?;
 
-    // check if the expiry date is valid
-    let expires =
-        expires_at.unwrap_or_else(|| env.block.time.plus_seconds(params.offer_expiry.min + 1));
-    params
-        .offer_expiry
-        .is_valid(&env.block, env.block.time, expires)?;
+    // resolve the params of every collection in the offer, overrides replace the global params
+    let offered_collections: Vec<&Addr> = offered_tokens
+        .iter()
+        .map(|token| &token.collection)
+        .collect();
+    let wanted_collections: Vec<&Addr> = wanted_tokens
+        .iter()
+        .map(|token| &token.collection)
+        .collect();
+    let mut collections: Vec<&Addr> = offered_collections
+        .iter()
+        .chain(wanted_collections.iter())
+        .copied()
+        .collect();
+    collections.sort();
+    collections.dedup();
+
+    let mut expiry_ranges = vec![];
+    for collection in collections {
+        let overrides = COLLECTION_PARAMS
+            .may_load(deps.storage, collection)?
+            .unwrap_or_default();
+
+        if let Some(max_tokens) = overrides.max_tokens {
+            let count = |side: &[&Addr]| side.iter().filter(|c| **c == collection).count() as u64;
+            if count(&offered_collections) > max_tokens || count(&wanted_collections) > max_tokens {
+                return Err(ContractError::MaxCollectionTokens {
+                    collection: collection.to_string(),
+                    limit: max_tokens,
+                });
+            }
+        }
+        expiry_ranges.push(
+            overrides
+                .offer_expiry
+                .unwrap_or_else(|| params.offer_expiry.clone()),
+        );
+    }
 
-    let api = deps.api;
+    // check if the expiry date is valid for all collections, the default is the earliest valid one
+    let min_expiry = expiry_ranges
+        .iter()
+        .map(|range| range.min)
+        .max()
+        .unwrap_or(params.offer_expiry.min);
+    let expires = expires_at.unwrap_or_else(|| env.block.time.plus_seconds(min_expiry + 1));
+    for range in expiry_ranges {
+        range.is_valid_for_creation(&env.block, expires)?;
+    }
 
     // Return an error if the amount of offers by this user + 1 exceeds the limit of active offers
-    if (offers_from_sender.offers.len() as u64) + 1 > params.max_offers {
+    let sender_stats = USER_STATS
+        .may_load(deps.storage, &sender)?
+        .unwrap_or_default();
+    if sender_stats.offers_sent + 1 > params.max_offers {
         return Err(ContractError::MaxOffers {
-            addr: info.sender.to_string(),
+            addr: sender.to_string(),
             max_offers: params.max_offers,
         });
     }
 
+    // Return an error if the amount of offers to the peer + 1 exceeds the limit of incoming offers
+    let peer_stats = USER_STATS
+        .may_load(deps.storage, &peer)?
+        .unwrap_or_default();
+    if peer_stats.offers_received + 1 > params.max_incoming_offers {
+        return Err(ContractError::MaxIncomingOffers {
+            addr: peer.to_string(),
+            max_offers: params.max_incoming_offers,
+        });
+    }
+
+    // Return an error if the peer doesn't accept this offer
+    let preferences = INBOX_PREFERENCES
+        .may_load(deps.storage, &peer)?
+        .unwrap_or_default();
+    if BLOCKED_SENDERS.has(deps.storage, (&peer, &sender)) || !preferences.accepts_sender(&sender) {
+        return Err(ContractError::SenderNotAccepted {
+            sender: sender.to_string(),
+            peer: peer.to_string(),
+        });
+    }
+    let offered_len = offered_tokens.len() as u64;
+    if offered_len < preferences.min_offered_nfts {
+        return Err(ContractError::MinOfferedNfts {
+            peer: peer.to_string(),
+            min: preferences.min_offered_nfts,
+        });
+    }
+
     // Return an error if the bundle size exceeds the bundle limit
-    if (offered_tokens.len() as u64) > params.bundle_limit
-        || (wanted_tokens.len() as u64) > params.bundle_limit
-    {
+    if offered_len > params.bundle_limit || (wanted_tokens.len() as u64) > params.bundle_limit {
         return Err(ContractError::MaxBundle {
             limit: params.bundle_limit,
         });
     }
 
+    if let Some(memo) = &memo {
+        if memo.len() as u64 > params.max_memo_length {
+            return Err(ContractError::MemoTooLong {
+                max: params.max_memo_length,
+            });
+        }
+    }
+
+    // check if the tokens arent already offered in another trade of the sender
+    let check_not_offered = |storage: &dyn Storage, token: &Token| -> Result<(), ContractError> {
+        let ids = offers()
+            .idx
+            .by_offered_token
+            .prefix(token.collection.clone(), token.token_id)
+            .keys(storage, None, None, Order::Ascending);
+        for id in ids {
+            let offer = offers().load(storage, id?)?;
+            if offer.sender == sender {
+                return Err(ContractError::TokenAlreadyOffered {
+                    collection: token.collection.to_string(),
+                    token_id: token.token_id,
+                    offer_id: offer.id,
+                });
+            }
+        }
+        Ok(())
+    };
+
     // Store data we're fetching in the next 2 loops for performance
     let mut offered_nfts: Vec<Token> = vec![];
     let mut wanted_nfts: Vec<Token> = vec![];
 
     // check if the peer is the owner of the requested tokens
     for token in wanted_tokens {
-        // Verify token collection addr
-        let collection = api.addr_validate(&token.collection)?;
-
-        let token = Token {
-            collection,
-            token_id: token.token_id,
-        };
-
         wanted_nfts.push(token.clone());
 
         if peer
@@ -89,41 +314,53 @@ pub fn execute_create_offer(
 
     // check if the sender is the owner of the tokens
     for token in offered_tokens {
-        // Verify token collection addr
-        let collection = api.addr_validate(&token.collection)?;
-
-        let token = Token {
-            collection,
-            token_id: token.token_id,
-        };
-
         offered_nfts.push(token.clone());
 
-        only_owner(deps.as_ref(), &info, &token.collection, token.token_id)?;
-
-        // check if the contract is approved to send transfer the tokens
-        Cw721Contract(token.collection.clone())
-            .approval(
-                &deps.querier,
-                token.token_id.to_string(),
-                env.contract.address.to_string(),
-                None,
-            )
-            .map_err(|_| ContractError::Unauthorized {
-                collection: token.collection.to_string(),
-                token_id: token.token_id,
-            })?;
+        // deposited tokens are already held by the contract, they leave the deposits
+        // of the sender while the offer is open
+        if escrowed {
+            only_depositor(deps.as_ref(), &sender, &token)?;
+            check_not_offered(deps.storage, &token)?;
+            deposits().remove(deps.storage, (token.collection.clone(), token.token_id))?;
+            continue;
+        }
 
-        // check if the tokens arent already offered in another trade
-        for offer in offers_from_sender.clone().offers {
-            if offer.offered_nfts.contains(&token) {
-                return Err(ContractError::TokenAlreadyOffered {
-                    collection: token.collection.into_string(),
+        only_owner(deps.as_ref(), &sender, &token.collection, token.token_id)?;
+
+        // check if the contract is approved to transfer the tokens until the offer expires
+        match approval_expiration(
+            &deps.querier,
+            &token,
+            sender.as_str(),
+            &env.contract.address,
+        ) {
+            None => {
+                return Err(ContractError::Unauthorized {
+                    collection: token.collection.to_string(),
                     token_id: token.token_id,
-                    offer_id: offer.id,
+                })
+            }
+            // the default expiry is the minimum, so it can't be clamped to the approval
+            Some(Expiration::AtTime(at)) if at < expires => {
+                return Err(ContractError::ApprovalExpiresBeforeOffer {
+                    collection: token.collection.to_string(),
+                    token_id: token.token_id,
+                    approval_expires: at,
+                    offer_expires: expires,
+                });
+            }
+            // block heights can't be compared with the offer expiry
+            Some(Expiration::AtHeight(height)) => {
+                return Err(ContractError::ApprovalExpiresAtHeight {
+                    collection: token.collection.to_string(),
+                    token_id: token.token_id,
+                    height,
                 });
             }
+            Some(_) => {}
         }
+
+        check_not_offered(deps.storage, &token)?;
     }
 
     // create and save offer
@@ -131,14 +368,22 @@ pub fn execute_create_offer(
         id: next_offer_id(deps.storage)?,
         offered_nfts,
         wanted_nfts,
-        sender: info.sender,
+        sender,
         peer,
         expires_at: expires,
         created_at: env.block.time,
+        escrowed,
+        memo,
     };
     offers().save(deps.storage, offer.id, &offer)?;
+    add_offer_to_stats(deps.storage, &offer)?;
+    update_trade_stats(deps.storage, |stats| stats.offers_created += 1)?;
+
+    let hooks = prepare_offer_hooks(deps.as_ref(), &offer, HookAction::Created)?;
 
     Ok(Response::new()
+        .add_submessages(hooks)
+        .add_event(offer_event("create-offer", &offer))
         .add_attribute("action", "create_offer")
         .add_attribute("offer_id", offer.id.to_string())
         .add_attribute("offer_sender", offer.sender)
@@ -157,8 +402,18 @@ pub fn execute_remove_offer(
     }
 
     offers().remove(deps.storage, offer.id)?;
+    remove_offer_from_stats(deps.storage, &offer)?;
+    update_trade_stats(deps.storage, |stats| stats.offers_removed += 1)?;
+    update_trade_history(deps.storage, &offer.sender, |history| {
+        history.offers_cancelled += 1
+    })?;
+    return_deposits(deps.storage, &offer)?;
+
+    let hooks = prepare_offer_hooks(deps.as_ref(), &offer, HookAction::Cancelled)?;
 
     Ok(Response::new()
+        .add_submessages(hooks)
+        .add_event(offer_event("remove-offer", &offer))
         .add_attribute("action", "revoke_offer")
         .add_attribute("offer_id", offer.id.to_string())
         .add_attribute("offer_sender", offer.sender)
@@ -173,94 +428,81 @@ pub fn execute_accept_offer(
 ) -> Result<Response, ContractError> {
     let offer = offers().load(deps.storage, id)?;
 
-    let params = SUDO_PARAMS.load(deps.storage)?;
-
-    // check if the sender is the peer of the offer
-    if offer.peer != info.sender {
-        return Err(ContractError::UnauthorizedSender {});
-    }
-
-    // check if the offer is not yet expired
-    params
-        .offer_expiry
-        .is_valid(&env.block, offer.created_at, offer.expires_at)?;
-
-    // check if the sender owns the requested nfts
-    for token in offer.wanted_nfts.clone() {
-        only_owner(deps.as_ref(), &info, &token.collection, token.token_id)?;
-
-        // check if the contract is approved to send transfer the tokens
-        Cw721Contract(token.collection.clone())
-            .approval(
-                &deps.querier,
-                token.token_id.to_string(),
-                env.contract.address.to_string(),
-                None,
-            )
-            .map_err(|_| ContractError::Unauthorized {
-                collection: token.collection.to_string(),
-                token_id: token.token_id,
-            })?;
-    }
-
-    // check if the offeror owns the offered nfts
-    for token in offer.offered_nfts.clone() {
-        if offer.sender
-            != Cw721Contract(token.collection.clone())
-                .owner_of(&deps.querier, token.token_id.to_string(), false)?
-                .owner
-        {
-            return Err(ContractError::UnauthorizedPeer {
-                collection: token.collection.into_string(),
-                token_id: token.token_id,
-                peer: offer.sender.to_string(),
-            });
-        }
-
-        // check if the contract is approved to send transfer the tokens
-        Cw721Contract(token.collection.clone())
-            .approval(
-                &deps.querier,
-                token.token_id.to_string(),
-                env.contract.address.to_string(),
-                None,
-            )
-            .map_err(|_| ContractError::UnauthorizedOperator {})?;
+    // fail on the first issue that blocks the trade
+    if let Some(issue) = check_accept(deps.as_ref(), &env, &offer, &info.sender)
+        .into_iter()
+        .next()
+    {
+        return Err(accept_issue_error(&offer, issue));
     }
     let mut res = Response::new();
 
     // remove the offer
     offers().remove(deps.storage, offer.id)?;
+    remove_offer_from_stats(deps.storage, &offer)?;
+    update_trade_stats(deps.storage, |stats| stats.offers_accepted += 1)?;
+    add_trade_to_collection_stats(deps.storage, &offer)?;
+    update_trade_history(deps.storage, &offer.sender, |history| {
+        history.trades_completed += 1
+    })?;
+    update_trade_history(deps.storage, &offer.peer, |history| {
+        history.trades_completed += 1
+    })?;
 
     // transfer nfts
-    transfer_nfts(offer.peer.to_string(), offer.offered_nfts.clone(), &mut res)?;
-    transfer_nfts(offer.sender.to_string(), offer.wanted_nfts, &mut res)?;
+    let transfers = [
+        transfers_to(&offer.peer, &offer.offered_nfts),
+        transfers_to(&offer.sender, &offer.wanted_nfts),
+    ]
+    .concat();
+    transfer_nfts(deps.storage, transfers, &mut res)?;
+
+    let hooks = prepare_offer_hooks(deps.as_ref(), &offer, HookAction::Accepted)?;
 
     Ok(res
+        .add_submessages(hooks)
+        .add_event(offer_event("accept-offer", &offer))
         .add_attribute("action", "accept_offer")
         .add_attribute("offer_id", offer.id.to_string())
         .add_attribute("offer_sender", offer.sender)
         .add_attribute("offer_peer", offer.peer))
 }
 
+/// Dispatches the transfers as submessages, a failing or ineffective transfer is
+/// reported in `reply`
 pub fn transfer_nfts(
-    recipient: String,
-    nfts: Vec<Token>,
-    res: &mut cosmwasm_std::Response<sg_std::StargazeMsgWrapper>,
+    storage: &mut dyn Storage,
+    transfers: Vec<Transfer>,
+    res: &mut Response,
 ) -> Result<(), ContractError> {
-    for token in nfts {
+    if transfers.is_empty() {
+        return Ok(());
+    }
+
+    let last = transfers.len() - 1;
+    for (index, transfer) in transfers.iter().enumerate() {
         let cw721_transfer_msg = Cw721ExecuteMsg::TransferNft {
-            recipient: recipient.clone(),
-            token_id: token.token_id.to_string(),
+            recipient: transfer.recipient.to_string(),
+            token_id: transfer.token.token_id.to_string(),
         };
         let exec_cw721_transfer_msg = WasmMsg::Execute {
-            contract_addr: token.collection.to_string(),
+            contract_addr: transfer.token.collection.to_string(),
             msg: to_binary(&cw721_transfer_msg)?,
             funds: vec![],
         };
 
-        res.messages.push(SubMsg::new(exec_cw721_transfer_msg));
+        // the reply id points to the transfer in PENDING_TRANSFERS. Once the last
+        // transfer succeeds, all transfers get verified in `reply`
+        let reply_id = TRANSFER_REPLY_ID + index as u64;
+        let sub_msg = if index == last {
+            SubMsg::reply_always(exec_cw721_transfer_msg, reply_id)
+        } else {
+            SubMsg::reply_on_error(exec_cw721_transfer_msg, reply_id)
+        };
+        res.messages.push(sub_msg);
     }
+    PENDING_TRANSFERS.save(storage, &transfers)?;
+
     Ok(())
 }
 
@@ -276,8 +518,20 @@ pub fn execute_reject_offer(
     }
 
     offers().remove(deps.storage, offer.id)?;
+    remove_offer_from_stats(deps.storage, &offer)?;
+    update_trade_stats(deps.storage, |stats| stats.offers_rejected += 1)?;
+    update_trade_history(deps.storage, &offer.sender, |history| {
+        history.rejections_received += 1
+    })?;
+    // escrowed NFTs aren't transferred back here, so a collection failing the
+    // transfer can't keep the offer open
+    return_deposits(deps.storage, &offer)?;
+
+    let hooks = prepare_offer_hooks(deps.as_ref(), &offer, HookAction::Rejected)?;
 
     Ok(Response::new()
+        .add_submessages(hooks)
+        .add_event(offer_event("reject-offer", &offer))
         .add_attribute("action", "reject_offer")
         .add_attribute("offer_id", offer.id.to_string())
         .add_attribute("offer_sender", offer.sender)
@@ -298,37 +552,351 @@ pub fn execute_remove_stale_offer(
 
     let offer = offers().load(deps.storage, id)?;
 
-    params
-        .offer_expiry
-        .is_valid(&env.block, offer.created_at, offer.expires_at)?;
+    // only offers that are past their expiry can be removed
+    if !offer.is_expired(&env.block) {
+        return Err(ContractError::OfferNotExpired { id: offer.id });
+    }
 
     offers().remove(deps.storage, id)?;
+    remove_offer_from_stats(deps.storage, &offer)?;
+    update_trade_stats(deps.storage, |stats| stats.offers_expired += 1)?;
+    update_trade_history(deps.storage, &offer.sender, |history| {
+        history.offers_expired += 1
+    })?;
+    return_deposits(deps.storage, &offer)?;
+
+    let hooks = prepare_offer_hooks(deps.as_ref(), &offer, HookAction::Cancelled)?;
 
     Ok(Response::new()
+        .add_submessages(hooks)
+        .add_event(offer_event("remove-stale-offer", &offer))
         .add_attribute("action", "remove_stale_offer")
         .add_attribute("offer_id", offer.id.to_string())
         .add_attribute("offer_sender", offer.sender)
         .add_attribute("offer_peer", offer.peer))
 }
 
+pub fn execute_set_inbox_preferences(
+    deps: DepsMut,
+    info: MessageInfo,
+    allowed_senders: Option<Vec<String>>,
+    min_offered_nfts: u64,
+) -> Result<Response, ContractError> {
+    let api = deps.api;
+
+    let preferences = InboxPreferences {
+        allowed_senders: allowed_senders
+            .map(|addrs| {
+                addrs
+                    .iter()
+                    .map(|addr| api.addr_validate(addr))
+                    .collect::<StdResult<_>>()
+            })
+            .transpose()?,
+        min_offered_nfts,
+    };
+    INBOX_PREFERENCES.save(deps.storage, &info.sender, &preferences)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_inbox_preferences")
+        .add_attribute("owner", info.sender))
+}
+
+pub fn execute_block_sender(
+    deps: DepsMut,
+    info: MessageInfo,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    if info.sender == sender {
+        return Err(ContractError::CannotBlockSelf {});
+    }
+
+    BLOCKED_SENDERS.save(deps.storage, (&info.sender, &sender), &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "block_sender")
+        .add_attribute("owner", info.sender)
+        .add_attribute("blocked_sender", sender))
+}
+
+pub fn execute_unblock_sender(
+    deps: DepsMut,
+    info: MessageInfo,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    if !BLOCKED_SENDERS.has(deps.storage, (&info.sender, &sender)) {
+        return Err(ContractError::SenderNotBlocked {
+            sender: sender.into_string(),
+        });
+    }
+
+    BLOCKED_SENDERS.remove(deps.storage, (&info.sender, &sender));
+
+    Ok(Response::new()
+        .add_attribute("action", "unblock_sender")
+        .add_attribute("owner", info.sender)
+        .add_attribute("unblocked_sender", sender))
+}
+
+pub fn execute_reject_blocked_offers(
+    deps: DepsMut,
+    info: MessageInfo,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    if !BLOCKED_SENDERS.has(deps.storage, (&info.sender, &sender)) {
+        return Err(ContractError::SenderNotBlocked {
+            sender: sender.into_string(),
+        });
+    }
+
+    // the offers received by a peer are bounded by max_incoming_offers, so this can be done in one go
+    let blocked_offers: Vec<Offer> = query_offers_by_peer(deps.as_ref(), info.sender.clone())?
+        .offers
+        .into_iter()
+        .filter(|offer| offer.sender == sender)
+        .collect();
+
+    let mut res = Response::new();
+    for offer in blocked_offers.iter() {
+        offers().remove(deps.storage, offer.id)?;
+        remove_offer_from_stats(deps.storage, offer)?;
+        update_trade_stats(deps.storage, |stats| stats.offers_rejected += 1)?;
+        update_trade_history(deps.storage, &offer.sender, |history| {
+            history.rejections_received += 1
+        })?;
+        return_deposits(deps.storage, offer)?;
+
+        let hooks = prepare_offer_hooks(deps.as_ref(), offer, HookAction::Rejected)?;
+        res = res
+            .add_submessages(hooks)
+            .add_event(offer_event("reject-offer", offer));
+    }
+
+    Ok(res
+        .add_attribute("action", "reject_blocked_offers")
+        .add_attribute("offer_peer", info.sender)
+        .add_attribute("offer_sender", sender)
+        .add_attribute("rejected_offers", blocked_offers.len().to_string()))
+}
+
+pub fn execute_update_admin_params(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_offers: Option<u64>,
+    max_incoming_offers: Option<u64>,
+    bundle_limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    only_admin(deps.as_ref(), &info.sender)?;
+
+    // the admin can only touch the offer limits, everything else stays with governance.
+    // The memo length decides how much storage an offer can take, so it's left out as well
+    sudo_update_params(
+        deps,
+        env,
+        ParamInfo {
+            offer_expiry: None,
+            maintainer: None,
+            max_offers,
+            max_incoming_offers,
+            bundle_limit,
+            max_memo_length: None,
+        },
+    )
+}
+
+pub fn execute_propose_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Addr,
+) -> Result<Response, ContractError> {
+    only_admin(deps.as_ref(), &info.sender)?;
+
+    PENDING_ADMIN.save(deps.storage, &admin)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_admin")
+        .add_attribute("admin", info.sender)
+        .add_attribute("pending_admin", admin))
+}
+
+pub fn execute_accept_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    if PENDING_ADMIN.may_load(deps.storage)? != Some(info.sender.clone()) {
+        return Err(ContractError::NotPendingAdmin {});
+    }
+
+    ADMIN.save(deps.storage, &info.sender)?;
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_admin")
+        .add_attribute("admin", info.sender))
+}
+
+pub fn execute_rebuild_offers(
+    deps: DepsMut,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if OFFERS_REBUILD.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoRebuildPending {});
+    }
+
+    let limit = limit
+        .unwrap_or(DEFAULT_REBUILD_LIMIT)
+        .clamp(1, MAX_REBUILD_LIMIT) as usize;
+    let complete = rebuild_offers(deps.storage, limit)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "rebuild_offers")
+        .add_attribute("complete", complete.to_string()))
+}
+
 // ---------------------------------------------------------------------------------
 // helper functions
 // ---------------------------------------------------------------------------------
 
+/// Validates the tokens of an offer. A token can only be in an offer once, on either
+/// side, as the second transfer of it would make the trade fail at accept.
+fn normalize_tokens(
+    api: &dyn Api,
+    offered_tokens: Vec<TokenMsg>,
+    wanted_tokens: Vec<TokenMsg>,
+) -> Result<(Vec<Token>, Vec<Token>), ContractError> {
+    let mut seen: BTreeSet<(Addr, TokenId)> = BTreeSet::new();
+
+    let mut normalize = |tokens: Vec<TokenMsg>| {
+        tokens
+            .into_iter()
+            .map(|token| {
+                let token = Token {
+                    collection: api.addr_validate(&token.collection)?,
+                    token_id: token.token_id,
+                };
+                if !seen.insert((token.collection.clone(), token.token_id)) {
+                    return Err(ContractError::DuplicateToken {
+                        collection: token.collection.into_string(),
+                        token_id: token.token_id,
+                    });
+                }
+                Ok(token)
+            })
+            .collect::<Result<Vec<_>, ContractError>>()
+    };
+
+    Ok((normalize(offered_tokens)?, normalize(wanted_tokens)?))
+}
+
+/// Checks to enforce only the admin can call
+fn only_admin(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    match ADMIN.may_load(deps.storage)? {
+        Some(admin) if admin == *sender => Ok(()),
+        _ => Err(ContractError::NotAdmin {}),
+    }
+}
+
 /// Checks to enfore only NFT owner can call
 fn only_owner(
     deps: Deps,
-    info: &MessageInfo,
+    sender: &Addr,
     collection: &Addr,
     token_id: u32,
 ) -> Result<OwnerOfResponse, ContractError> {
     let res =
         Cw721Contract(collection.clone()).owner_of(&deps.querier, token_id.to_string(), false)?;
-    if res.owner != info.sender {
+    if res.owner != *sender {
         return Err(ContractError::UnauthorizedSender {});
     }
 
     Ok(res)
 }
 
+/// Checks to enforce only the depositor of a token can use it
+fn only_depositor(deps: Deps, sender: &Addr, token: &Token) -> Result<(), ContractError> {
+    let depositor =
+        deposits().may_load(deps.storage, (token.collection.clone(), token.token_id))?;
+    if depositor.as_ref() != Some(sender) {
+        return Err(ContractError::NotDeposited {
+            collection: token.collection.to_string(),
+            token_id: token.token_id,
+        });
+    }
+
+    Ok(())
+}
+
+/// Custom event with the full offer, so indexers can follow offers without
+/// querying them. Every token adds a collection & token id attribute pair.
+fn offer_event(action: &str, offer: &Offer) -> Event {
+    let token_attributes = |side: &str, nfts: &[Token]| {
+        nfts.iter()
+            .flat_map(|token| {
+                [
+                    attr(format!("{}_collection", side), token.collection.to_string()),
+                    attr(format!("{}_token_id", side), token.token_id.to_string()),
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // attribute values can't be empty, so the memo is only added if there is one
+    let memo = offer
+        .memo
+        .iter()
+        .filter(|memo| !memo.is_empty())
+        .map(|memo| attr("memo", memo));
+
+    Event::new(format!("pegasus-{}", action))
+        .add_attribute("offer_id", offer.id.to_string())
+        .add_attribute("sender", offer.sender.to_string())
+        .add_attribute("peer", offer.peer.to_string())
+        .add_attribute("created_at", offer.created_at.to_string())
+        .add_attribute("expires_at", offer.expires_at.to_string())
+        .add_attribute("escrowed", offer.escrowed.to_string())
+        .add_attributes(memo)
+        .add_attributes(token_attributes("offered", &offer.offered_nfts))
+        .add_attributes(token_attributes("wanted", &offer.wanted_nfts))
+}
+
+/// Notifies the registered offer hooks, a failing hook is caught in `reply`
+fn prepare_offer_hooks(deps: Deps, offer: &Offer, action: HookAction) -> StdResult<Vec<SubMsg>> {
+    OFFER_HOOKS.prepare_hooks(deps.storage, |hook| {
+        let msg = OfferHookMsg::new(offer.clone());
+        let execute = WasmMsg::Execute {
+            contract_addr: hook.to_string(),
+            msg: msg.into_binary(action.clone())?,
+            funds: vec![],
+        };
+        Ok(SubMsg::reply_on_error(execute, OFFER_HOOK_REPLY_ID))
+    })
+}
+
+/// Maps an issue found by `check_accept` to the error of `execute_accept_offer`
+fn accept_issue_error(offer: &Offer, issue: AcceptIssue) -> ContractError {
+    match issue {
+        AcceptIssue::NotPeer { .. } => ContractError::UnauthorizedSender {},
+        AcceptIssue::Expired { .. } => ContractError::OfferExpired { id: offer.id },
+        AcceptIssue::WantedNotOwned { .. } => ContractError::UnauthorizedSender {},
+        AcceptIssue::WantedNotApproved { token } => ContractError::Unauthorized {
+            collection: token.collection.into_string(),
+            token_id: token.token_id,
+        },
+        AcceptIssue::OfferedNotOwned { token, .. } => ContractError::UnauthorizedPeer {
+            collection: token.collection.into_string(),
+            token_id: token.token_id,
+            peer: offer.sender.to_string(),
+        },
+        AcceptIssue::OfferedNotApproved { .. } => ContractError::UnauthorizedOperator {},
+    }
+}
+
+/// Transfers of all given NFTs to a single recipient
+fn transfers_to(recipient: &Addr, nfts: &[Token]) -> Vec<Transfer> {
+    nfts.iter()
+        .map(|token| Transfer {
+            token: token.clone(),
+            recipient: recipient.clone(),
+        })
+        .collect()
+}
+
 // fn finalize_trade(deps: Deps, offered: Vec<Token>) {}
@@ -1,15 +1,34 @@
-use crate::msg::ExecuteMsg;
-use cosmwasm_std::{to_binary, Addr, BlockInfo, StdError, StdResult, Timestamp, WasmMsg};
+use crate::msg::{
+    AcceptIssue, AdminResponse, BlockedSendersResponse, CollectionParamsResponse,
+    CollectionStatsResponse, ConfigResponse, DepositsResponse, ExecuteMsg, FilteredOffersResponse,
+    InboxPreferencesResponse, OfferFilter, OfferResponse, OfferStatusResponse,
+    OffersHealthResponse, OffersResponse, ParamsResponse, QueryMsg, ReceiveNftMsg,
+    ReputationResponse, SimulateAcceptResponse, StatsResponse, TokenInterestResponse, TokenMsg,
+    UserStatsResponse,
+};
+use crate::state::{Offer, Token, TokenId, TradeSide};
+use cosmwasm_std::{
+    to_binary, Addr, BlockInfo, Deps, Env, QuerierWrapper, StdError, StdResult, Timestamp, WasmMsg,
+};
+use cw721::Cw721ExecuteMsg;
+use cw721_base::helpers::Cw721Contract;
+use cw_utils::Expiration;
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sg_controllers::HooksResponse;
 use sg_std::CosmosMsg;
 use thiserror::Error;
 
-/// MarketplaceContract is a wrapper around Addr that provides a lot of helpers
+/// Page size when looking up operator approvals, the maximum of cw721-base
+const OPERATORS_PAGE_LIMIT: u32 = 100;
+
+/// PegasusContract is a wrapper around Addr that provides typed helpers
+/// for other contracts to execute and query Pegasus
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MarketplaceContract(pub Addr);
+pub struct PegasusContract(pub Addr);
 
-impl MarketplaceContract {
+impl PegasusContract {
     pub fn addr(&self) -> Addr {
         self.0.clone()
     }
@@ -23,6 +42,521 @@ impl MarketplaceContract {
         }
         .into())
     }
+
+    pub fn query<T: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        req: QueryMsg,
+    ) -> StdResult<T> {
+        querier.query_wasm_smart(self.addr(), &req)
+    }
+
+    /*** execute ***/
+
+    pub fn create_offer<T: Into<String>>(
+        &self,
+        offered_nfts: Vec<TokenMsg>,
+        wanted_nfts: Vec<TokenMsg>,
+        peer: T,
+        expires_at: Option<Timestamp>,
+        memo: Option<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::CreateOffer {
+            offered_nfts,
+            wanted_nfts,
+            peer: peer.into(),
+            expires_at,
+            memo,
+        })
+    }
+
+    pub fn create_escrowed_offer<T: Into<String>>(
+        &self,
+        offered_nfts: Vec<TokenMsg>,
+        wanted_nfts: Vec<TokenMsg>,
+        peer: T,
+        expires_at: Option<Timestamp>,
+        memo: Option<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::CreateEscrowedOffer {
+            offered_nfts,
+            wanted_nfts,
+            peer: peer.into(),
+            expires_at,
+            memo,
+        })
+    }
+
+    /// Deposits `token_id` of `collection` by sending it to Pegasus.
+    /// The message has to be executed by the owner of the token.
+    pub fn deposit_nft(&self, collection: Addr, token_id: TokenId) -> StdResult<CosmosMsg> {
+        let send_msg = Cw721ExecuteMsg::SendNft {
+            contract: self.addr().into(),
+            token_id: token_id.to_string(),
+            msg: to_binary(&ReceiveNftMsg::Deposit {})?,
+        };
+        Ok(WasmMsg::Execute {
+            contract_addr: collection.into(),
+            msg: to_binary(&send_msg)?,
+            funds: vec![],
+        }
+        .into())
+    }
+
+    pub fn withdraw_deposits(&self, nfts: Vec<TokenMsg>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::WithdrawDeposits { nfts })
+    }
+
+    pub fn remove_offer(&self, id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RemoveOffer { id })
+    }
+
+    pub fn accept_offer(&self, id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::AcceptOffer { id })
+    }
+
+    pub fn reject_offer(&self, id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RejectOffer { id })
+    }
+
+    pub fn remove_stale_offer(&self, id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RemoveStaleOffer { id })
+    }
+
+    pub fn set_inbox_preferences(
+        &self,
+        allowed_senders: Option<Vec<String>>,
+        min_offered_nfts: u64,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetInboxPreferences {
+            allowed_senders,
+            min_offered_nfts,
+        })
+    }
+
+    pub fn block_sender<T: Into<String>>(&self, sender: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::BlockSender {
+            sender: sender.into(),
+        })
+    }
+
+    pub fn unblock_sender<T: Into<String>>(&self, sender: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UnblockSender {
+            sender: sender.into(),
+        })
+    }
+
+    pub fn reject_blocked_offers<T: Into<String>>(&self, sender: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RejectBlockedOffers {
+            sender: sender.into(),
+        })
+    }
+
+    pub fn update_admin_params(
+        &self,
+        max_offers: Option<u64>,
+        max_incoming_offers: Option<u64>,
+        bundle_limit: Option<u64>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateAdminParams {
+            max_offers,
+            max_incoming_offers,
+            bundle_limit,
+        })
+    }
+
+    pub fn propose_admin<T: Into<String>>(&self, admin: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ProposeAdmin {
+            admin: admin.into(),
+        })
+    }
+
+    pub fn accept_admin(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::AcceptAdmin {})
+    }
+
+    pub fn rebuild_offers(&self, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RebuildOffers { limit })
+    }
+
+    /*** queries ***/
+
+    pub fn offer(&self, querier: &QuerierWrapper, id: u64) -> StdResult<OfferResponse> {
+        self.query(querier, QueryMsg::Offer { id })
+    }
+
+    pub fn offers_by_sender<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        sender: T,
+    ) -> StdResult<OffersResponse> {
+        self.query(
+            querier,
+            QueryMsg::OffersBySender {
+                sender: sender.into(),
+            },
+        )
+    }
+
+    pub fn offers_by_peer<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        peer: T,
+    ) -> StdResult<OffersResponse> {
+        self.query(querier, QueryMsg::OffersByPeer { peer: peer.into() })
+    }
+
+    pub fn user_stats<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        address: T,
+    ) -> StdResult<UserStatsResponse> {
+        self.query(
+            querier,
+            QueryMsg::UserStats {
+                address: address.into(),
+            },
+        )
+    }
+
+    pub fn stats(&self, querier: &QuerierWrapper) -> StdResult<StatsResponse> {
+        self.query(querier, QueryMsg::Stats {})
+    }
+
+    pub fn collection_stats<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        collection: T,
+    ) -> StdResult<CollectionStatsResponse> {
+        self.query(
+            querier,
+            QueryMsg::CollectionStats {
+                collection: collection.into(),
+            },
+        )
+    }
+
+    pub fn token_interest<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        collection: T,
+        token_id: TokenId,
+    ) -> StdResult<TokenInterestResponse> {
+        self.query(
+            querier,
+            QueryMsg::TokenInterest {
+                collection: collection.into(),
+                token_id,
+            },
+        )
+    }
+
+    pub fn fillable_offers<T: Into<String>, C: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        owner: T,
+        collection: C,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<OffersResponse> {
+        self.query(
+            querier,
+            QueryMsg::FillableOffers {
+                owner: owner.into(),
+                collection: collection.into(),
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn reputation<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        address: T,
+    ) -> StdResult<ReputationResponse> {
+        self.query(
+            querier,
+            QueryMsg::Reputation {
+                address: address.into(),
+            },
+        )
+    }
+
+    pub fn inbox_preferences<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        address: T,
+    ) -> StdResult<InboxPreferencesResponse> {
+        self.query(
+            querier,
+            QueryMsg::InboxPreferences {
+                address: address.into(),
+            },
+        )
+    }
+
+    pub fn deposits<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        owner: T,
+        start_after: Option<TokenMsg>,
+        limit: Option<u32>,
+    ) -> StdResult<DepositsResponse> {
+        self.query(
+            querier,
+            QueryMsg::Deposits {
+                owner: owner.into(),
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn blocked_senders<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        owner: T,
+    ) -> StdResult<BlockedSendersResponse> {
+        self.query(
+            querier,
+            QueryMsg::BlockedSenders {
+                owner: owner.into(),
+            },
+        )
+    }
+
+    pub fn offer_hooks(&self, querier: &QuerierWrapper) -> StdResult<HooksResponse> {
+        self.query(querier, QueryMsg::OfferHooks {})
+    }
+
+    pub fn admin(&self, querier: &QuerierWrapper) -> StdResult<AdminResponse> {
+        self.query(querier, QueryMsg::Admin {})
+    }
+
+    pub fn simulate_accept<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        id: u64,
+        accepter: T,
+    ) -> StdResult<SimulateAcceptResponse> {
+        self.query(
+            querier,
+            QueryMsg::SimulateAccept {
+                id,
+                accepter: accepter.into(),
+            },
+        )
+    }
+
+    pub fn offer_status(
+        &self,
+        querier: &QuerierWrapper,
+        id: u64,
+    ) -> StdResult<OfferStatusResponse> {
+        self.query(querier, QueryMsg::OfferStatus { id })
+    }
+
+    pub fn offers_health(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<OffersHealthResponse> {
+        self.query(querier, QueryMsg::OffersHealth { start_after, limit })
+    }
+
+    pub fn collection_params<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        collection: T,
+    ) -> StdResult<CollectionParamsResponse> {
+        self.query(
+            querier,
+            QueryMsg::CollectionParams {
+                collection: collection.into(),
+            },
+        )
+    }
+
+    pub fn offers_by_collection<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        collection: T,
+        side: TradeSide,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<OffersResponse> {
+        self.query(
+            querier,
+            QueryMsg::OffersByCollection {
+                collection: collection.into(),
+                side,
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn offers(
+        &self,
+        querier: &QuerierWrapper,
+        filter: OfferFilter,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<FilteredOffersResponse> {
+        self.query(
+            querier,
+            QueryMsg::Offers {
+                filter,
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn config(&self, querier: &QuerierWrapper) -> StdResult<ConfigResponse> {
+        self.query(querier, QueryMsg::Config {})
+    }
+
+    pub fn params(&self, querier: &QuerierWrapper) -> StdResult<ParamsResponse> {
+        self.query(querier, QueryMsg::Params {})
+    }
+}
+
+/// Current owner of a token, None if it can't be queried
+pub fn token_owner(querier: &QuerierWrapper, token: &Token) -> Option<String> {
+    Cw721Contract(token.collection.clone())
+        .owner_of(querier, token.token_id.to_string(), false)
+        .map(|res| res.owner)
+        .ok()
+}
+
+/// Expiration of the approval `spender` has to transfer the token of `owner`, either
+/// for the token itself or as operator (`ApproveAll`). The longest lasting approval
+/// is returned, None if there's no approval that hasn't expired yet.
+pub fn approval_expiration(
+    querier: &QuerierWrapper,
+    token: &Token,
+    owner: &str,
+    spender: &Addr,
+) -> Option<Expiration> {
+    let cw721 = Cw721Contract(token.collection.clone());
+
+    let token_approval = cw721
+        .approval(
+            querier,
+            token.token_id.to_string(),
+            spender.to_string(),
+            None,
+        )
+        .map(|res| res.approval.expires)
+        .ok();
+
+    // operators are sorted by address, page through them until the spender is found
+    let mut operator_approval = None;
+    let mut start_after = None;
+    while let Ok(operators) = cw721.all_operators(
+        querier,
+        owner,
+        false,
+        start_after,
+        Some(OPERATORS_PAGE_LIMIT),
+    ) {
+        if let Some(operator) = operators.iter().find(|op| op.spender == *spender) {
+            operator_approval = Some(operator.expires);
+            break;
+        }
+        if operators.len() < OPERATORS_PAGE_LIMIT as usize {
+            break;
+        }
+        start_after = operators.last().map(|op| op.spender.clone());
+    }
+
+    match (token_approval, operator_approval) {
+        (Some(a), Some(b)) => Some(if outlasts(&b, &a) { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Checks if `spender` is currently approved to transfer the token of `owner`
+pub fn is_approved(querier: &QuerierWrapper, token: &Token, owner: &str, spender: &Addr) -> bool {
+    approval_expiration(querier, token, owner, spender).is_some()
+}
+
+fn outlasts(a: &Expiration, b: &Expiration) -> bool {
+    match (a, b) {
+        (_, Expiration::Never {}) => false,
+        (Expiration::Never {}, _) => true,
+        (Expiration::AtTime(a), Expiration::AtTime(b)) => a > b,
+        (Expiration::AtHeight(a), Expiration::AtHeight(b)) => a > b,
+        // prefer time based approvals, only they can be checked against an offer expiry
+        (Expiration::AtTime(_), Expiration::AtHeight(_)) => true,
+        _ => false,
+    }
+}
+
+/// Runs every check of `execute_accept_offer` and collects what blocks the trade
+pub fn check_accept(deps: Deps, env: &Env, offer: &Offer, accepter: &Addr) -> Vec<AcceptIssue> {
+    let mut issues = vec![];
+
+    // check if the accepter is the peer of the offer
+    if offer.peer != *accepter {
+        issues.push(AcceptIssue::NotPeer {
+            peer: offer.peer.clone(),
+        });
+    }
+
+    // check if the offer is not yet expired
+    if offer.is_expired(&env.block) {
+        issues.push(AcceptIssue::Expired {
+            expires_at: offer.expires_at,
+        });
+    }
+
+    // check if the accepter owns the requested nfts & approved the contract
+    for token in offer.wanted_nfts.iter() {
+        let owner = token_owner(&deps.querier, token);
+        if owner.as_deref() != Some(accepter.as_str()) {
+            issues.push(AcceptIssue::WantedNotOwned {
+                token: token.clone(),
+                owner,
+            });
+        } else if !is_approved(
+            &deps.querier,
+            token,
+            accepter.as_str(),
+            &env.contract.address,
+        ) {
+            issues.push(AcceptIssue::WantedNotApproved {
+                token: token.clone(),
+            });
+        }
+    }
+
+    // check if the offeror owns the offered nfts, unless they are held in escrow
+    if !offer.escrowed {
+        for token in offer.offered_nfts.iter() {
+            let owner = token_owner(&deps.querier, token);
+            if owner.as_deref() != Some(offer.sender.as_str()) {
+                issues.push(AcceptIssue::OfferedNotOwned {
+                    token: token.clone(),
+                    owner,
+                });
+            } else if !is_approved(
+                &deps.querier,
+                token,
+                offer.sender.as_str(),
+                &env.contract.address,
+            ) {
+                issues.push(AcceptIssue::OfferedNotApproved {
+                    token: token.clone(),
+                });
+            }
+        }
+    }
+
+    issues
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -48,15 +582,15 @@ impl ExpiryRange {
         ExpiryRange { min, max }
     }
 
-    /// Validates if given expires time is within the allowable range
-    pub fn is_valid(
+    /// Validates if the expiry time of a new offer is within the allowable range
+    /// (min, max], counted from the current block time
+    pub fn is_valid_for_creation(
         &self,
         block: &BlockInfo,
-        created_at: Timestamp,
         expires: Timestamp,
     ) -> Result<(), ExpiryRangeError> {
         let now = block.time;
-        if !(expires > created_at.plus_seconds(self.min) && expires <= now.plus_seconds(self.max)) {
+        if !(expires > now.plus_seconds(self.min) && expires <= now.plus_seconds(self.max)) {
             return Err(ExpiryRangeError::InvalidExpirationRange {});
         }
 
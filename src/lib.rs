@@ -4,6 +4,7 @@ mod execute;
 mod helpers;
 pub mod msg;
 mod query;
+mod reply;
 pub mod state;
 mod sudo;
 
@@ -12,4 +13,4 @@ mod multitest;
 #[cfg(test)]
 mod unit_tests;
 pub use error::ContractError;
-pub use helpers::{ExpiryRange, ExpiryRangeError, MarketplaceContract};
+pub use helpers::{ExpiryRange, ExpiryRangeError, PegasusContract};
@@ -1,8 +1,12 @@
 use crate::{
     helpers::ExpiryRange,
-    state::{Offer, SudoParams},
+    state::{
+        CollectionParams, CollectionStats, InboxPreferences, Offer, SudoParams, Token,
+        TradeHistory, TradeSide, TradeStats, UserStats,
+    },
 };
-use cosmwasm_std::Timestamp;
+use cosmwasm_std::{to_binary, Addr, Binary, Decimal, StdResult, Timestamp};
+use cw721::Cw721ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -18,8 +22,17 @@ pub struct InstantiateMsg {
     /// Maximum amount of offers that can be sent by a user
     pub max_offers: u64,
 
+    /// Maximum amount of offers that can be received by a user
+    pub max_incoming_offers: u64,
+
     /// Maximum amount of NFTs in bundle
     pub bundle_limit: u64,
+
+    /// Maximum length of an offer memo in bytes
+    pub max_memo_length: u64,
+
+    /// Address that can update the limits without governance
+    pub admin: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -37,6 +50,7 @@ pub enum ExecuteMsg {
         wanted_nfts: Vec<TokenMsg>,
         peer: String,
         expires_at: Option<Timestamp>,
+        memo: Option<String>,
     },
     /// Remove an offer (called by sender)
     RemoveOffer { id: u64 },
@@ -44,8 +58,53 @@ pub enum ExecuteMsg {
     AcceptOffer { id: u64 },
     /// Reject an existing offer (called by peer)
     RejectOffer { id: u64 },
+    /// Deposit an NFT by sending it with cw721 `SendNft`
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Create a new offer of deposited NFTs, which stay in escrow until the offer closes.
+    /// Without a trade they are credited back to the deposits of the sender.
+    CreateEscrowedOffer {
+        offered_nfts: Vec<TokenMsg>,
+        wanted_nfts: Vec<TokenMsg>,
+        peer: String,
+        expires_at: Option<Timestamp>,
+        memo: Option<String>,
+    },
+    /// Transfer deposited NFTs back to their owner
+    WithdrawDeposits { nfts: Vec<TokenMsg> },
     /// Operation to remove stale offers (called by anyone & incentivized)
     RemoveStaleOffer { id: u64 },
+    /// Restrict which offers can be sent to the caller
+    SetInboxPreferences {
+        allowed_senders: Option<Vec<String>>,
+        min_offered_nfts: u64,
+    },
+    /// Block a sender from sending offers to the caller
+    BlockSender { sender: String },
+    /// Unblock a previously blocked sender
+    UnblockSender { sender: String },
+    /// Reject all open offers from a blocked sender to the caller
+    RejectBlockedOffers { sender: String },
+    /// Update the offer limits (called by admin)
+    UpdateAdminParams {
+        max_offers: Option<u64>,
+        max_incoming_offers: Option<u64>,
+        bundle_limit: Option<u64>,
+    },
+    /// Propose a new admin (called by admin)
+    ProposeAdmin { admin: String },
+    /// Become the admin (called by the proposed admin)
+    AcceptAdmin {},
+    /// Rebuild a batch of the offers stored before the last migration (called by anyone).
+    /// Other operations are unavailable until every offer has been rebuilt.
+    RebuildOffers { limit: Option<u32> },
+}
+
+/// Payload of a cw721 `SendNft` to this contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveNftMsg {
+    /// Credit the received NFT to the deposits of its sender
+    Deposit {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -57,19 +116,125 @@ pub enum SudoMsg {
         offer_expiry: Option<ExpiryRange>,
         maintainer: Option<String>,
         max_offers: Option<u64>,
+        max_incoming_offers: Option<u64>,
         bundle_limit: Option<u64>,
+        max_memo_length: Option<u64>,
+    },
+    /// Set or remove the admin, clears any pending admin
+    UpdateAdmin { admin: Option<String> },
+    /// Override the trade params of a collection
+    SetCollectionParams {
+        collection: String,
+        max_tokens: Option<u64>,
+        offer_expiry: Option<ExpiryRange>,
     },
+    /// Remove the overrides of a collection
+    RemoveCollectionParams { collection: String },
+    /// Add a contract that gets notified of offer lifecycle events
+    AddOfferHook { hook: String },
+    /// Remove a previously added offer hook
+    RemoveOfferHook { hook: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    Offer { id: u64 },
-    OffersBySender { sender: String },
-    OffersByPeer { peer: String },
+    Offer {
+        id: u64,
+    },
+    OffersBySender {
+        sender: String,
+    },
+    OffersByPeer {
+        peer: String,
+    },
+    UserStats {
+        address: String,
+    },
+    Stats {},
+    CollectionStats {
+        collection: String,
+    },
+    Reputation {
+        address: String,
+    },
+    TokenInterest {
+        collection: String,
+        token_id: u32,
+    },
+    FillableOffers {
+        owner: String,
+        collection: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    InboxPreferences {
+        address: String,
+    },
+    Deposits {
+        owner: String,
+        start_after: Option<TokenMsg>,
+        limit: Option<u32>,
+    },
+    BlockedSenders {
+        owner: String,
+    },
+    OfferHooks {},
+    Admin {},
+    SimulateAccept {
+        id: u64,
+        accepter: String,
+    },
+    OfferStatus {
+        id: u64,
+    },
+    OffersHealth {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    OffersByCollection {
+        collection: String,
+        side: TradeSide,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Offers {
+        filter: OfferFilter,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Config {},
+    CollectionParams {
+        collection: String,
+    },
     Params {},
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OfferFilterStatus {
+    Open,
+    Expired,
+}
+
+/// Criteria offers have to match, unset fields match any offer
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct OfferFilter {
+    pub sender: Option<String>,
+    pub peer: Option<String>,
+    /// Collection of an offered or wanted NFT
+    pub collection: Option<String>,
+    pub status: Option<OfferFilterStatus>,
+    pub expires_before: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FilteredOffersResponse {
+    pub offers: Vec<Offer>,
+    /// Id to continue from if the scan stopped early, None if all offers were scanned
+    pub last_scanned: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OfferResponse {
     pub offer: Option<Offer>,
@@ -84,3 +249,187 @@ pub struct OffersResponse {
 pub struct ParamsResponse {
     pub params: SudoParams,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserStatsResponse {
+    pub stats: UserStats,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub stats: TradeStats,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionStatsResponse {
+    pub stats: CollectionStats,
+}
+
+/// Demand for an NFT among the open offers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenInterestResponse {
+    /// Unexpired offers that want the NFT
+    pub open_offers: u64,
+    /// Collections of the NFTs offered in exchange for it
+    pub offered_collections: Vec<Addr>,
+    /// Creation times of the latest open offers, newest first
+    pub latest_offers: Vec<Timestamp>,
+    /// Whether all offers that want the NFT were scanned, the counts only cover the
+    /// most recent offers otherwise
+    pub complete: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReputationResponse {
+    pub history: TradeHistory,
+    /// Share of the closed offers that ended in a trade, unset without any closed offers
+    pub reputation: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InboxPreferencesResponse {
+    pub preferences: InboxPreferences,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositsResponse {
+    pub deposits: Vec<Token>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlockedSendersResponse {
+    pub senders: Vec<Addr>,
+}
+
+/// Inclusive range a limit can be set to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LimitBounds {
+    pub min: u64,
+    pub max: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub offer_expiry: ExpiryRange,
+    pub max_offers: u64,
+    pub max_incoming_offers: u64,
+    pub bundle_limit: u64,
+    pub max_memo_length: u64,
+    /// Bounds governance and the admin can set the limits to
+    pub offer_expiry_bounds: ExpiryRange,
+    pub max_offers_bounds: LimitBounds,
+    pub max_incoming_offers_bounds: LimitBounds,
+    pub bundle_limit_bounds: LimitBounds,
+    pub max_memo_length_bounds: LimitBounds,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionParamsResponse {
+    pub params: CollectionParams,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminResponse {
+    pub admin: Option<Addr>,
+    pub pending_admin: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookAction {
+    Created,
+    Accepted,
+    Rejected,
+    Cancelled,
+}
+
+/// Payload sent to registered offer hooks
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OfferHookMsg {
+    pub offer: Offer,
+}
+
+impl OfferHookMsg {
+    pub fn new(offer: Offer) -> Self {
+        OfferHookMsg { offer }
+    }
+
+    /// serializes the message
+    pub fn into_binary(self, action: HookAction) -> StdResult<Binary> {
+        let msg = match action {
+            HookAction::Created => OfferHookExecuteMsg::OfferCreatedHook(self),
+            HookAction::Accepted => OfferHookExecuteMsg::OfferAcceptedHook(self),
+            HookAction::Rejected => OfferHookExecuteMsg::OfferRejectedHook(self),
+            HookAction::Cancelled => OfferHookExecuteMsg::OfferCancelledHook(self),
+        };
+        to_binary(&msg)
+    }
+}
+
+/// Hook contracts should include these variants in their ExecuteMsg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OfferHookExecuteMsg {
+    OfferCreatedHook(OfferHookMsg),
+    OfferAcceptedHook(OfferHookMsg),
+    OfferRejectedHook(OfferHookMsg),
+    OfferCancelledHook(OfferHookMsg),
+}
+
+/// Reason an offer can't be accepted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AcceptIssue {
+    /// Only the peer of the offer can accept it
+    NotPeer { peer: Addr },
+    /// The offer has expired
+    Expired { expires_at: Timestamp },
+    /// The accepter doesn't own a wanted token (anymore)
+    WantedNotOwned { token: Token, owner: Option<String> },
+    /// The contract isn't approved to transfer a wanted token
+    WantedNotApproved { token: Token },
+    /// The sender doesn't own an offered token anymore
+    OfferedNotOwned { token: Token, owner: Option<String> },
+    /// The contract isn't approved to transfer an offered token anymore
+    OfferedNotApproved { token: Token },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateAcceptResponse {
+    /// Everything that blocks the trade, empty if the offer can be accepted
+    pub issues: Vec<AcceptIssue>,
+}
+
+/// Whether a token of an offer can still be traded
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenStatus {
+    pub token: Token,
+    /// Current owner, None if the token doesn't exist anymore
+    pub owner: Option<String>,
+    /// Owned by the sender (offered), the contract (escrowed) or the peer (wanted)
+    pub owned: bool,
+    /// The contract is approved to transfer the token
+    pub approved: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OfferStatus {
+    pub id: u64,
+    pub expired: bool,
+    pub offered_nfts: Vec<TokenStatus>,
+    pub wanted_nfts: Vec<TokenStatus>,
+    /// False if the offer can't be accepted anymore: it expired, an offered token
+    /// moved or lost its approval, or a wanted token moved. Missing approvals on
+    /// wanted tokens are expected until the peer accepts.
+    pub valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OfferStatusResponse {
+    pub status: OfferStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OffersHealthResponse {
+    pub offers: Vec<OfferStatus>,
+}
@@ -1,18 +1,27 @@
 #[cfg(test)]
-use cosmwasm_std::{coins, Addr, Coin, Empty, Timestamp};
-use cw721::{Cw721QueryMsg, OwnerOfResponse};
+use cosmwasm_std::{
+    coins, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env, MessageInfo,
+    Response, StdError, StdResult, Timestamp,
+};
+use cw721::{Cw721QueryMsg, Cw721ReceiveMsg, OwnerOfResponse};
 use cw721_base::msg::{ExecuteMsg as Cw721ExecuteMsg, MintMsg};
+use cw721_base::ContractError as Cw721BaseError;
 use cw_utils::Expiration;
 use sg721::msg::InstantiateMsg as Sg721InstantiateMsg;
 use sg721::state::CollectionInfo;
 
 use cw_multi_test::{BankSudo, Contract, ContractWrapper, Executor, SudoMsg as CwSudoMsg};
+use sg_controllers::HooksResponse;
 use sg_multi_test::StargazeApp;
 
 use sg_std::{StargazeMsgWrapper, NATIVE_DENOM};
 
-use crate::msg::{ExecuteMsg, OffersResponse, QueryMsg, TokenMsg};
-use crate::ContractError;
+use crate::msg::{
+    AcceptIssue, ExecuteMsg, OfferHookExecuteMsg, OfferResponse, OffersResponse, QueryMsg,
+    ReceiveNftMsg, SudoMsg, TokenMsg, UserStatsResponse,
+};
+use crate::state::Token;
+use crate::{ContractError, ExpiryRange, PegasusContract};
 
 const CREATOR: &str = "creator";
 const COLLECTION_A: &str = "collection-a";
@@ -38,7 +47,56 @@ pub fn contract_p2p_trade() -> Box<dyn Contract<StargazeMsgWrapper>> {
         crate::contract::instantiate,
         crate::contract::query,
     )
-    .with_sudo(crate::contract::sudo);
+    .with_sudo(crate::contract::sudo)
+    .with_reply(crate::contract::reply);
+    Box::new(contract)
+}
+
+fn hook_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: OfferHookExecuteMsg,
+) -> StdResult<Response> {
+    let action = match msg {
+        OfferHookExecuteMsg::OfferCreatedHook(_) => "created",
+        OfferHookExecuteMsg::OfferAcceptedHook(_) => "accepted",
+        OfferHookExecuteMsg::OfferRejectedHook(_) => "rejected",
+        OfferHookExecuteMsg::OfferCancelledHook(_) => "cancelled",
+    };
+    Ok(Response::new().add_attribute("hook_action", action))
+}
+
+fn broken_hook_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: OfferHookExecuteMsg,
+) -> StdResult<Response> {
+    Err(StdError::generic_err("broken hook"))
+}
+
+fn hook_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn hook_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_binary(&Empty {})
+}
+
+pub fn contract_hook() -> Box<dyn Contract<StargazeMsgWrapper>> {
+    let contract = ContractWrapper::new_with_empty(hook_execute, hook_instantiate, hook_query);
+    Box::new(contract)
+}
+
+pub fn contract_broken_hook() -> Box<dyn Contract<StargazeMsgWrapper>> {
+    let contract =
+        ContractWrapper::new_with_empty(broken_hook_execute, hook_instantiate, hook_query);
     Box::new(contract)
 }
 
@@ -51,6 +109,82 @@ pub fn contract_sg721() -> Box<dyn Contract<StargazeMsgWrapper>> {
     Box::new(contract)
 }
 
+fn failing_transfer_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ExecuteMsg<Empty>,
+) -> Result<sg_std::Response, Cw721BaseError> {
+    match msg {
+        Cw721ExecuteMsg::TransferNft { .. } => {
+            Err(StdError::generic_err("transfers are disabled").into())
+        }
+        _ => sg721::contract::execute(deps, env, info, msg),
+    }
+}
+
+/// sg721 collection of which every TransferNft fails
+pub fn contract_failing_transfer_sg721() -> Box<dyn Contract<StargazeMsgWrapper>> {
+    let contract = ContractWrapper::new(
+        failing_transfer_execute,
+        sg721::contract::instantiate,
+        sg721::contract::query,
+    );
+    Box::new(contract)
+}
+
+fn noop_transfer_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ExecuteMsg<Empty>,
+) -> Result<sg_std::Response, Cw721BaseError> {
+    match msg {
+        Cw721ExecuteMsg::TransferNft { .. } => Ok(sg_std::Response::new()),
+        _ => sg721::contract::execute(deps, env, info, msg),
+    }
+}
+
+/// sg721 collection that accepts every TransferNft without moving the token
+pub fn contract_noop_transfer_sg721() -> Box<dyn Contract<StargazeMsgWrapper>> {
+    let contract = ContractWrapper::new(
+        noop_transfer_execute,
+        sg721::contract::instantiate,
+        sg721::contract::query,
+    );
+    Box::new(contract)
+}
+
+fn setup_collection(
+    router: &mut StargazeApp,
+    creator: &Addr,
+    contract: Box<dyn Contract<StargazeMsgWrapper>>,
+) -> Addr {
+    let code_id = router.store_code(contract);
+    let msg = Sg721InstantiateMsg {
+        name: COLLECTION_A.to_string(),
+        symbol: "MAU".to_string(),
+        minter: CREATOR.to_string(),
+        collection_info: CollectionInfo {
+            creator: CREATOR.to_string(),
+            description: "test".to_string(),
+            image: "ipfs://test".to_string(),
+            external_link: None,
+            royalty_info: None,
+        },
+    };
+    router
+        .instantiate_contract(
+            code_id,
+            creator.clone(),
+            &msg,
+            &coins(1_000_000_000, NATIVE_DENOM),
+            "NFT",
+            Some(creator.to_string()),
+        )
+        .unwrap()
+}
+
 fn setup_block_time(router: &mut StargazeApp, seconds: u64) {
     let mut block = router.block_info();
     block.time = Timestamp::from_seconds(seconds);
@@ -70,7 +204,10 @@ fn setup_contracts(
         },
         maintainer: CREATOR.to_string(),
         max_offers: 16,
+        max_incoming_offers: 16,
         bundle_limit: 3,
+        max_memo_length: 64,
+        admin: None,
     };
     let p2p_trade = router
         .instantiate_contract(
@@ -222,12 +359,27 @@ fn approve(
     let approve_msg = Cw721ExecuteMsg::<Empty>::Approve {
         spender: marketplace.to_string(),
         token_id: token_id.to_string(),
-        expires: expires,
+        expires,
     };
     let res = router.execute_contract(creator.clone(), collection.clone(), &approve_msg, &[]);
     assert!(res.is_ok());
 }
 
+fn approve_all(
+    router: &mut StargazeApp,
+    owner: &Addr,
+    collection: &Addr,
+    operator: &Addr,
+    expires: Option<Expiration>,
+) {
+    let approve_all_msg = Cw721ExecuteMsg::<Empty>::ApproveAll {
+        operator: operator.to_string(),
+        expires,
+    };
+    let res = router.execute_contract(owner.clone(), collection.clone(), &approve_all_msg, &[]);
+    assert!(res.is_ok());
+}
+
 fn _transfer(
     router: &mut StargazeApp,
     creator: &Addr,
@@ -268,6 +420,7 @@ fn create_offer() {
         }],
         peer: peer.to_string(),
         expires_at: None,
+        memo: None,
     };
 
     // empty offer is not allowed
@@ -293,16 +446,16 @@ fn create_offer() {
                 token_id: TOKEN1_ID,
             },
             TokenMsg {
-                collection: collection_a.to_string(),
+                collection: collection_b.to_string(),
                 token_id: TOKEN1_ID,
             },
             TokenMsg {
                 collection: collection_a.to_string(),
-                token_id: TOKEN1_ID,
+                token_id: TOKEN3_ID,
             },
             TokenMsg {
                 collection: collection_a.to_string(),
-                token_id: TOKEN1_ID,
+                token_id: TOKEN4_ID,
             },
         ],
         wanted_nfts: vec![TokenMsg {
@@ -311,6 +464,7 @@ fn create_offer() {
         }],
         peer: peer.to_string(),
         expires_at: None,
+        memo: None,
     };
 
     // sender should fail to create a offer if the nfts are not approved yet
@@ -340,6 +494,7 @@ fn create_offer() {
         }],
         peer: peer.to_string(),
         expires_at: None,
+        memo: None,
     };
 
     // sender should fail to create a offer if the nfts are not approved yet
@@ -400,6 +555,18 @@ fn create_offer() {
         TOKEN1_ID
     );
 
+    let stats: UserStatsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            trade_contract.clone(),
+            &QueryMsg::UserStats {
+                address: sender.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(stats.stats.offers_sent, 1);
+    assert_eq!(stats.stats.offers_received, 0);
+
     // if the token is already being offered by the sender in another offer, the tx should fail
     let exec_create_not_owned_by_peer_msg = ExecuteMsg::CreateOffer {
         offered_nfts: vec![TokenMsg {
@@ -412,6 +579,7 @@ fn create_offer() {
         }],
         peer: peer.to_string(),
         expires_at: None,
+        memo: None,
     };
 
     let err = router
@@ -443,6 +611,7 @@ fn create_offer() {
         }],
         peer: peer.to_string(),
         expires_at: None,
+        memo: None,
     };
 
     let err = router
@@ -470,6 +639,7 @@ fn create_offer() {
         }],
         peer: peer.to_string(),
         expires_at: None,
+        memo: None,
     };
 
     let err = router
@@ -527,6 +697,7 @@ fn accept_offer() {
         }],
         peer: peer.to_string(),
         expires_at: None,
+        memo: None,
     };
 
     // Approves contract on the sender side
@@ -539,6 +710,36 @@ fn accept_offer() {
         Some(Expiration::AtTime(Timestamp::from_seconds(1900))),
     );
 
+    // the approval expires before the offer does
+    let err = router
+        .execute_contract(
+            sender.clone(),
+            trade_contract.clone(),
+            &exec_create_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ApprovalExpiresBeforeOffer {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID,
+            approval_expires: Timestamp::from_seconds(1900),
+            offer_expires: Timestamp::from_seconds(1000 + MIN_EXPIRY + 1),
+        }
+    );
+
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN1_ID,
+        Some(Expiration::AtTime(Timestamp::from_seconds(
+            1000 + MAX_EXPIRY,
+        ))),
+    );
+
     let res = router.execute_contract(
         sender.clone(),
         trade_contract.clone(),
@@ -589,9 +790,33 @@ fn accept_offer() {
         None,
     );
 
-    let res = router.execute_contract(peer.clone(), trade_contract, &exec_accept_msg, &[]);
+    let res = router.execute_contract(peer.clone(), trade_contract.clone(), &exec_accept_msg, &[]);
     assert!(res.is_ok());
 
+    // the custom event carries the whole offer
+    let res = res.unwrap();
+    let event = res
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm-pegasus-accept-offer")
+        .unwrap();
+    let attribute = |key: &str| {
+        event
+            .attributes
+            .iter()
+            .filter(|a| a.key == key)
+            .map(|a| a.value.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(attribute("offer_id"), vec!["1"]);
+    assert_eq!(attribute("sender"), vec![sender.to_string()]);
+    assert_eq!(
+        attribute("offered_collection"),
+        vec![collection_a.to_string()]
+    );
+    assert_eq!(attribute("offered_token_id"), vec![TOKEN1_ID.to_string()]);
+    assert_eq!(attribute("wanted_token_id"), vec![TOKEN2_ID.to_string()]);
+
     // check if the NFTs are transfered properly
     let owner_of_1_query_msg = Cw721QueryMsg::OwnerOf {
         token_id: TOKEN1_ID.to_string(),
@@ -608,13 +833,1587 @@ fn accept_offer() {
         .unwrap();
     let res2: OwnerOfResponse = router
         .wrap()
-        .query_wasm_smart(collection_a, &owner_of_2_query_msg)
+        .query_wasm_smart(collection_a.clone(), &owner_of_2_query_msg)
         .unwrap();
 
     assert_eq!(res1.owner, peer.to_string());
     assert_eq!(res2.owner, sender.to_string());
 
+    // the trade is counted in the aggregate stats
+    let pegasus = PegasusContract(trade_contract);
+    let stats = pegasus.stats(&router.wrap()).unwrap().stats;
+    assert_eq!(stats.offers_created, 1);
+    assert_eq!(stats.offers_accepted, 1);
+    let stats = pegasus
+        .collection_stats(&router.wrap(), &collection_a)
+        .unwrap()
+        .stats;
+    assert_eq!(stats.trades, 1);
+    assert_eq!(stats.tokens_traded, 2);
+    let stats = pegasus
+        .collection_stats(&router.wrap(), &collection_b)
+        .unwrap()
+        .stats;
+    assert_eq!(stats.trades, 0);
+
+    // both traders get a completed trade on their record
+    for trader in [&sender, &peer].iter() {
+        let res = pegasus.reputation(&router.wrap(), *trader).unwrap();
+        assert_eq!(res.history.trades_completed, 1);
+        assert_eq!(res.reputation, Some(Decimal::one()));
+    }
+
     // test when peer accepts whithout ownership first send
 
     // test if the contract
 }
+
+#[test]
+fn same_token_id_in_two_collections() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, collection_b) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &collection_b, TOKEN1_ID);
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+    approve(
+        router,
+        &peer,
+        &collection_b,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+
+    // the same id in another collection is a different token
+    let msg = pegasus
+        .create_offer(
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            vec![TokenMsg {
+                collection: collection_b.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            &peer,
+            None,
+            None,
+        )
+        .unwrap();
+    router.execute(sender.clone(), msg).unwrap();
+
+    router
+        .execute(peer.clone(), pegasus.accept_offer(1).unwrap())
+        .unwrap();
+
+    let owner_of = |router: &StargazeApp, collection: &Addr| -> String {
+        let res: OwnerOfResponse = router
+            .wrap()
+            .query_wasm_smart(
+                collection,
+                &Cw721QueryMsg::OwnerOf {
+                    token_id: TOKEN1_ID.to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        res.owner
+    };
+    assert_eq!(owner_of(router, &collection_a), peer);
+    assert_eq!(owner_of(router, &collection_b), sender);
+}
+
+#[test]
+fn offer_expiry() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+    approve(
+        router,
+        &peer,
+        &collection_a,
+        &trade_contract,
+        TOKEN2_ID,
+        None,
+    );
+
+    let create_msg = |expires_at: u64| ExecuteMsg::CreateOffer {
+        offered_nfts: vec![TokenMsg {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID,
+        }],
+        wanted_nfts: vec![TokenMsg {
+            collection: collection_a.to_string(),
+            token_id: TOKEN2_ID,
+        }],
+        peer: peer.to_string(),
+        expires_at: Some(Timestamp::from_seconds(expires_at)),
+        memo: None,
+    };
+
+    // ------ TESTCASE: expiry outside of the (min, max] range ----------
+    for expires_at in [1000 + MIN_EXPIRY, 1000 + MAX_EXPIRY + 1] {
+        let err = router
+            .execute_contract(
+                sender.clone(),
+                trade_contract.clone(),
+                &create_msg(expires_at),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ExpiryRange(crate::ExpiryRangeError::InvalidExpirationRange {})
+        );
+    }
+
+    // the upper bound of the range is inclusive
+    let res = router.execute_contract(
+        sender.clone(),
+        trade_contract.clone(),
+        &create_msg(1000 + MAX_EXPIRY),
+        &[],
+    );
+    assert!(res.is_ok(), "Offer at max expiry should be valid.");
+    let res = router.execute_contract(
+        sender.clone(),
+        trade_contract.clone(),
+        &ExecuteMsg::RemoveOffer { id: 1 },
+        &[],
+    );
+    assert!(res.is_ok());
+
+    // ------ TESTCASE: stale offers can only be removed once expired ----------
+    let expires_at = 1000 + MIN_EXPIRY + 1;
+    let res = router.execute_contract(
+        sender.clone(),
+        trade_contract.clone(),
+        &create_msg(expires_at),
+        &[],
+    );
+    assert!(res.is_ok(), "Offer at min expiry + 1 should be valid.");
+
+    setup_block_time(router, expires_at - 1);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::RemoveStaleOffer { id: 2 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::OfferNotExpired { id: 2 }
+    );
+
+    // ------ TESTCASE: offers can't be accepted once expired ----------
+    setup_block_time(router, expires_at);
+    let err = router
+        .execute_contract(
+            peer.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::AcceptOffer { id: 2 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::OfferExpired { id: 2 }
+    );
+
+    let res = router.execute_contract(
+        creator.clone(),
+        trade_contract.clone(),
+        &ExecuteMsg::RemoveStaleOffer { id: 2 },
+        &[],
+    );
+    assert!(res.is_ok(), "Expired offer should be removable.");
+
+    // ------ TESTCASE: offers can be accepted right before expiry ----------
+    let expires_at = expires_at + MIN_EXPIRY + 1;
+    let res = router.execute_contract(
+        sender.clone(),
+        trade_contract.clone(),
+        &create_msg(expires_at),
+        &[],
+    );
+    assert!(res.is_ok());
+
+    setup_block_time(router, expires_at - 1);
+    let res = router.execute_contract(
+        peer.clone(),
+        trade_contract,
+        &ExecuteMsg::AcceptOffer { id: 3 },
+        &[],
+    );
+    assert!(res.is_ok(), "Offer should be accepted before expiry.");
+
+    let owner: OwnerOfResponse = router
+        .wrap()
+        .query_wasm_smart(
+            collection_a,
+            &Cw721QueryMsg::OwnerOf {
+                token_id: TOKEN1_ID.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(owner.owner, peer.to_string());
+}
+
+#[test]
+fn escrowed_offer() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, collection_b) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &sender, &creator, &collection_b, TOKEN1_ID);
+    mint_for(router, &sender, &creator, &collection_a, TOKEN3_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+
+    let owner_of = |router: &mut StargazeApp, collection: &Addr, token_id: u32| -> String {
+        let res: OwnerOfResponse = router
+            .wrap()
+            .query_wasm_smart(
+                collection,
+                &Cw721QueryMsg::OwnerOf {
+                    token_id: token_id.to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        res.owner
+    };
+
+    // deposited NFTs don't need an approval
+    for collection in [&collection_a, &collection_b] {
+        let msg = pegasus.deposit_nft(collection.clone(), TOKEN1_ID).unwrap();
+        router.execute(sender.clone(), msg).unwrap();
+        assert_eq!(owner_of(router, collection, TOKEN1_ID), trade_contract);
+    }
+    let res = pegasus
+        .deposits(&router.wrap(), &sender, None, None)
+        .unwrap();
+    assert_eq!(res.deposits.len(), 2);
+
+    let bundle = vec![
+        TokenMsg {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID,
+        },
+        TokenMsg {
+            collection: collection_b.to_string(),
+            token_id: TOKEN1_ID,
+        },
+    ];
+    let wanted_nfts = vec![TokenMsg {
+        collection: collection_a.to_string(),
+        token_id: TOKEN2_ID,
+    }];
+
+    // only the depositor can offer the deposited NFTs
+    let msg = pegasus
+        .create_escrowed_offer(
+            bundle.clone(),
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN3_ID,
+            }],
+            &sender,
+            None,
+            None,
+        )
+        .unwrap();
+    let err = router.execute(peer.clone(), msg).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NotDeposited {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID
+        }
+    );
+
+    // an escrowed bundle counts for the sender like any other offer
+    let msg = pegasus
+        .create_escrowed_offer(bundle.clone(), wanted_nfts.clone(), &peer, None, None)
+        .unwrap();
+    router.execute(sender.clone(), msg).unwrap();
+    let offer = pegasus.offer(&router.wrap(), 1).unwrap().offer.unwrap();
+    assert!(offer.escrowed);
+    assert_eq!(offer.offered_nfts.len(), 2);
+    let res = pegasus.user_stats(&router.wrap(), &sender).unwrap();
+    assert_eq!(res.stats.offers_sent, 1);
+
+    // offered NFTs leave the deposits until the offer closes
+    let res = pegasus
+        .deposits(&router.wrap(), &sender, None, None)
+        .unwrap();
+    assert!(res.deposits.is_empty());
+    let msg = pegasus.withdraw_deposits(bundle.clone()).unwrap();
+    let err = router.execute(sender.clone(), msg).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NotDeposited {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID
+        }
+    );
+
+    // rejecting the offer credits the NFTs back to the sender
+    router
+        .execute(peer.clone(), pegasus.reject_offer(1).unwrap())
+        .unwrap();
+    let res = pegasus
+        .deposits(&router.wrap(), &sender, None, None)
+        .unwrap();
+    assert_eq!(res.deposits.len(), 2);
+    assert_eq!(owner_of(router, &collection_a, TOKEN1_ID), trade_contract);
+
+    // withdraw one of them and trade the other one
+    let msg = pegasus.withdraw_deposits(vec![bundle[1].clone()]).unwrap();
+    router.execute(sender.clone(), msg).unwrap();
+    assert_eq!(owner_of(router, &collection_b, TOKEN1_ID), sender);
+
+    let msg = pegasus
+        .create_escrowed_offer(vec![bundle[0].clone()], wanted_nfts, &peer, None, None)
+        .unwrap();
+    router.execute(sender.clone(), msg).unwrap();
+    approve(
+        router,
+        &peer,
+        &collection_a,
+        &trade_contract,
+        TOKEN2_ID,
+        None,
+    );
+    router
+        .execute(peer.clone(), pegasus.accept_offer(2).unwrap())
+        .unwrap();
+    assert_eq!(owner_of(router, &collection_a, TOKEN1_ID), peer);
+    assert_eq!(owner_of(router, &collection_a, TOKEN2_ID), sender);
+    let res = pegasus
+        .deposits(&router.wrap(), &sender, None, None)
+        .unwrap();
+    assert!(res.deposits.is_empty());
+}
+
+#[test]
+fn spoofed_receive_nft() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (victim, attacker, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, collection_b) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &victim, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &attacker, &creator, &collection_a, TOKEN2_ID);
+    approve_all(router, &victim, &collection_a, &trade_contract, None);
+
+    // any contract can call ReceiveNft and claim to forward an NFT of the victim,
+    // collection_b stands in for a contract controlled by the attacker
+    let receive_msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: victim.to_string(),
+        token_id: TOKEN1_ID.to_string(),
+        msg: to_binary(&ReceiveNftMsg::Deposit {}).unwrap(),
+    });
+    router
+        .execute_contract(
+            collection_b.clone(),
+            trade_contract.clone(),
+            &receive_msg,
+            &[],
+        )
+        .unwrap();
+
+    // the deposit is a token of the calling contract, the NFT of the victim isn't touched
+    let res = pegasus
+        .deposits(&router.wrap(), &victim, None, None)
+        .unwrap();
+    assert_eq!(
+        res.deposits,
+        vec![Token {
+            collection: collection_b.clone(),
+            token_id: TOKEN1_ID,
+        }]
+    );
+    let res = pegasus.offers_by_sender(&router.wrap(), &victim).unwrap();
+    assert!(res.offers.is_empty());
+
+    // and only the victim can offer it
+    let msg = pegasus
+        .create_escrowed_offer(
+            vec![TokenMsg {
+                collection: collection_b.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            &victim,
+            None,
+            None,
+        )
+        .unwrap();
+    let err = router.execute(attacker.clone(), msg).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NotDeposited {
+            collection: collection_b.to_string(),
+            token_id: TOKEN1_ID
+        }
+    );
+
+    // escrowed offers are sent by the verified depositor, so the inbox
+    // restrictions of the peer apply to them
+    let msg = pegasus
+        .deposit_nft(collection_a.clone(), TOKEN2_ID)
+        .unwrap();
+    router.execute(attacker.clone(), msg).unwrap();
+    let escrowed_offer = pegasus
+        .create_escrowed_offer(
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN2_ID,
+            }],
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            &victim,
+            None,
+            None,
+        )
+        .unwrap();
+    let not_accepted = ContractError::SenderNotAccepted {
+        sender: attacker.to_string(),
+        peer: victim.to_string(),
+    };
+
+    router
+        .execute(victim.clone(), pegasus.block_sender(&attacker).unwrap())
+        .unwrap();
+    let err = router
+        .execute(attacker.clone(), escrowed_offer.clone())
+        .unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), not_accepted);
+
+    router
+        .execute(victim.clone(), pegasus.unblock_sender(&attacker).unwrap())
+        .unwrap();
+    let msg = pegasus
+        .set_inbox_preferences(Some(vec![creator.to_string()]), 0)
+        .unwrap();
+    router.execute(victim.clone(), msg).unwrap();
+    let err = router.execute(attacker, escrowed_offer).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), not_accepted);
+}
+
+#[test]
+fn escrow_failing_collection() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (victim, attacker, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let broken_collection = setup_collection(router, &creator, contract_failing_transfer_sg721());
+    let pegasus = PegasusContract(trade_contract);
+
+    mint_for(router, &victim, &creator, &collection_a, TOKEN1_ID);
+
+    // escrowed offers of NFTs that can't be transferred out of the contract
+    let broken_tokens = [TOKEN1_ID, TOKEN2_ID, TOKEN3_ID];
+    for token_id in broken_tokens {
+        mint_for(router, &attacker, &creator, &broken_collection, token_id);
+        let msg = pegasus
+            .deposit_nft(broken_collection.clone(), token_id)
+            .unwrap();
+        router.execute(attacker.clone(), msg).unwrap();
+        let msg = pegasus
+            .create_escrowed_offer(
+                vec![TokenMsg {
+                    collection: broken_collection.to_string(),
+                    token_id,
+                }],
+                vec![TokenMsg {
+                    collection: collection_a.to_string(),
+                    token_id: TOKEN1_ID,
+                }],
+                &victim,
+                None,
+                None,
+            )
+            .unwrap();
+        router.execute(attacker.clone(), msg).unwrap();
+    }
+    let res = pegasus.user_stats(&router.wrap(), &victim).unwrap();
+    assert_eq!(res.stats.offers_received, 3);
+
+    // closing the offers doesn't depend on the collection
+    router
+        .execute(victim.clone(), pegasus.reject_offer(1).unwrap())
+        .unwrap();
+
+    setup_block_time(router, 1000 + MAX_EXPIRY);
+    router
+        .execute(creator, pegasus.remove_stale_offer(2).unwrap())
+        .unwrap();
+
+    router
+        .execute(victim.clone(), pegasus.block_sender(&attacker).unwrap())
+        .unwrap();
+    router
+        .execute(
+            victim.clone(),
+            pegasus.reject_blocked_offers(&attacker).unwrap(),
+        )
+        .unwrap();
+
+    let res = pegasus.user_stats(&router.wrap(), &victim).unwrap();
+    assert_eq!(res.stats.offers_received, 0);
+
+    // the NFTs are credited back to the sender, only withdrawing them fails
+    let res = pegasus
+        .deposits(&router.wrap(), &attacker, None, None)
+        .unwrap();
+    assert_eq!(res.deposits.len(), broken_tokens.len());
+    let msg = pegasus
+        .withdraw_deposits(vec![TokenMsg {
+            collection: broken_collection.to_string(),
+            token_id: TOKEN1_ID,
+        }])
+        .unwrap();
+    let err = router.execute(attacker, msg).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TransferFailed {
+            collection: broken_collection.to_string(),
+            token_id: TOKEN1_ID
+        }
+    );
+}
+
+#[test]
+fn pegasus_contract_helper() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, collection_b) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &sender, &creator, &collection_b, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+    approve(
+        router,
+        &sender,
+        &collection_b,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+
+    let wanted_nfts = vec![TokenMsg {
+        collection: collection_a.to_string(),
+        token_id: TOKEN2_ID,
+    }];
+
+    let msg = pegasus
+        .create_offer(
+            vec![TokenMsg {
+                collection: collection_b.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            wanted_nfts.clone(),
+            &peer,
+            None,
+            None,
+        )
+        .unwrap();
+    let res = router.execute(sender.clone(), msg);
+    assert!(res.is_ok(), "Offer should be created through the helper.");
+
+    let msg = pegasus
+        .deposit_nft(collection_a.clone(), TOKEN1_ID)
+        .unwrap();
+    let res = router.execute(sender.clone(), msg);
+    assert!(res.is_ok(), "NFT should be deposited through the helper.");
+    let msg = pegasus
+        .create_escrowed_offer(
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            wanted_nfts,
+            &peer,
+            None,
+            None,
+        )
+        .unwrap();
+    let res = router.execute(sender.clone(), msg);
+    assert!(
+        res.is_ok(),
+        "Escrowed offer should be created through the helper."
+    );
+
+    let res = pegasus.offers_by_sender(&router.wrap(), &sender).unwrap();
+    assert_eq!(res.offers.len(), 2);
+    let res = pegasus.offer(&router.wrap(), 2).unwrap();
+    assert!(res.offer.unwrap().escrowed);
+    let res = pegasus.user_stats(&router.wrap(), &peer).unwrap();
+    assert_eq!(res.stats.offers_received, 2);
+
+    let msg = pegasus.reject_offer(2).unwrap();
+    let res = router.execute(peer.clone(), msg);
+    assert!(res.is_ok());
+
+    let res = pegasus.offers_by_peer(&router.wrap(), &peer).unwrap();
+    assert_eq!(res.offers.len(), 1);
+    let res = pegasus
+        .deposits(&router.wrap(), &sender, None, None)
+        .unwrap();
+    assert_eq!(res.deposits.len(), 1);
+}
+
+#[test]
+fn offer_hooks() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+
+    let hook_id = router.store_code(contract_hook());
+    let hook = router
+        .instantiate_contract(hook_id, creator.clone(), &Empty {}, &[], "hook", None)
+        .unwrap();
+    let broken_hook_id = router.store_code(contract_broken_hook());
+    let broken_hook = router
+        .instantiate_contract(
+            broken_hook_id,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "hook",
+            None,
+        )
+        .unwrap();
+
+    for hook in [&hook, &broken_hook] {
+        let res = router.wasm_sudo(
+            trade_contract.clone(),
+            &SudoMsg::AddOfferHook {
+                hook: hook.to_string(),
+            },
+        );
+        assert!(res.is_ok());
+    }
+
+    let hooks: HooksResponse = router
+        .wrap()
+        .query_wasm_smart(trade_contract.clone(), &QueryMsg::OfferHooks {})
+        .unwrap();
+    assert_eq!(hooks.hooks, vec![hook.to_string(), broken_hook.to_string()]);
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+    approve(
+        router,
+        &peer,
+        &collection_a,
+        &trade_contract,
+        TOKEN2_ID,
+        None,
+    );
+
+    let hook_actions = |res: &cw_multi_test::AppResponse| -> Vec<String> {
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .filter(|attr| attr.key == "hook_action")
+            .map(|attr| attr.value.clone())
+            .collect()
+    };
+
+    let exec_create_msg = ExecuteMsg::CreateOffer {
+        offered_nfts: vec![TokenMsg {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID,
+        }],
+        wanted_nfts: vec![TokenMsg {
+            collection: collection_a.to_string(),
+            token_id: TOKEN2_ID,
+        }],
+        peer: peer.to_string(),
+        expires_at: None,
+        memo: None,
+    };
+    let res = router
+        .execute_contract(
+            sender.clone(),
+            trade_contract.clone(),
+            &exec_create_msg,
+            &[],
+        )
+        .unwrap();
+    assert_eq!(hook_actions(&res), vec!["created"]);
+
+    // the broken hook can't block the trade
+    let res = router
+        .execute_contract(
+            peer.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::AcceptOffer { id: 1 },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(hook_actions(&res), vec!["accepted"]);
+    assert!(res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .any(|attr| attr.key == "offer_hook_error"));
+
+    let res = router.wasm_sudo(
+        trade_contract.clone(),
+        &SudoMsg::RemoveOfferHook {
+            hook: broken_hook.to_string(),
+        },
+    );
+    assert!(res.is_ok());
+
+    let hooks: HooksResponse = router
+        .wrap()
+        .query_wasm_smart(trade_contract, &QueryMsg::OfferHooks {})
+        .unwrap();
+    assert_eq!(hooks.hooks, vec![hook.to_string()]);
+}
+
+#[test]
+fn failed_settlement() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let broken_collection = setup_collection(router, &creator, contract_failing_transfer_sg721());
+
+    mint_for(router, &sender, &creator, &broken_collection, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+    approve(
+        router,
+        &sender,
+        &broken_collection,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+    approve(
+        router,
+        &peer,
+        &collection_a,
+        &trade_contract,
+        TOKEN2_ID,
+        None,
+    );
+
+    let exec_create_msg = ExecuteMsg::CreateOffer {
+        offered_nfts: vec![TokenMsg {
+            collection: broken_collection.to_string(),
+            token_id: TOKEN1_ID,
+        }],
+        wanted_nfts: vec![TokenMsg {
+            collection: collection_a.to_string(),
+            token_id: TOKEN2_ID,
+        }],
+        peer: peer.to_string(),
+        expires_at: None,
+        memo: None,
+    };
+    let res = router.execute_contract(
+        sender.clone(),
+        trade_contract.clone(),
+        &exec_create_msg,
+        &[],
+    );
+    assert!(res.is_ok());
+
+    // the failing transfer is reported and the whole trade reverts
+    let err = router
+        .execute_contract(
+            peer.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::AcceptOffer { id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TransferFailed {
+            collection: broken_collection.to_string(),
+            token_id: TOKEN1_ID
+        }
+    );
+
+    let owner: OwnerOfResponse = router
+        .wrap()
+        .query_wasm_smart(
+            collection_a,
+            &Cw721QueryMsg::OwnerOf {
+                token_id: TOKEN2_ID.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(owner.owner, peer.to_string());
+
+    let qres: OfferResponse = router
+        .wrap()
+        .query_wasm_smart(trade_contract, &QueryMsg::Offer { id: 1 })
+        .unwrap();
+    assert!(qres.offer.is_some(), "Offer should still exist.");
+}
+
+#[test]
+fn unverified_settlement() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let broken_collection = setup_collection(router, &creator, contract_noop_transfer_sg721());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &broken_collection, TOKEN2_ID);
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+    approve(
+        router,
+        &peer,
+        &broken_collection,
+        &trade_contract,
+        TOKEN2_ID,
+        None,
+    );
+
+    let exec_create_msg = ExecuteMsg::CreateOffer {
+        offered_nfts: vec![TokenMsg {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID,
+        }],
+        wanted_nfts: vec![TokenMsg {
+            collection: broken_collection.to_string(),
+            token_id: TOKEN2_ID,
+        }],
+        peer: peer.to_string(),
+        expires_at: None,
+        memo: None,
+    };
+    let res = router.execute_contract(
+        sender.clone(),
+        trade_contract.clone(),
+        &exec_create_msg,
+        &[],
+    );
+    assert!(res.is_ok());
+
+    // the peer's token never moves, so the sender must keep its token as well
+    let err = router
+        .execute_contract(
+            peer.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::AcceptOffer { id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::OwnershipNotTransferred {
+            collection: broken_collection.to_string(),
+            token_id: TOKEN2_ID,
+            recipient: sender.to_string(),
+        }
+    );
+
+    let owner: OwnerOfResponse = router
+        .wrap()
+        .query_wasm_smart(
+            collection_a,
+            &Cw721QueryMsg::OwnerOf {
+                token_id: TOKEN1_ID.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(owner.owner, sender.to_string());
+}
+
+#[test]
+fn simulate_accept() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+
+    let msg = pegasus
+        .create_offer(
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN2_ID,
+            }],
+            &peer,
+            None,
+            None,
+        )
+        .unwrap();
+    router.execute(sender.clone(), msg).unwrap();
+
+    let offered = Token {
+        collection: collection_a.clone(),
+        token_id: TOKEN1_ID,
+    };
+    let wanted = Token {
+        collection: collection_a.clone(),
+        token_id: TOKEN2_ID,
+    };
+
+    // the sender can't accept & the peer didn't approve yet
+    let res = pegasus.simulate_accept(&router.wrap(), 1, &sender).unwrap();
+    assert_eq!(
+        res.issues,
+        vec![
+            AcceptIssue::NotPeer { peer: peer.clone() },
+            AcceptIssue::WantedNotOwned {
+                token: wanted.clone(),
+                owner: Some(peer.to_string()),
+            },
+        ]
+    );
+    let res = pegasus.simulate_accept(&router.wrap(), 1, &peer).unwrap();
+    assert_eq!(
+        res.issues,
+        vec![AcceptIssue::WantedNotApproved {
+            token: wanted.clone()
+        }]
+    );
+
+    // nothing blocks the trade after the peer approved
+    approve(
+        router,
+        &peer,
+        &collection_a,
+        &trade_contract,
+        TOKEN2_ID,
+        None,
+    );
+    let res = pegasus.simulate_accept(&router.wrap(), 1, &peer).unwrap();
+    assert!(res.issues.is_empty());
+
+    // the sender moves the offered token away
+    _transfer(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    let res = pegasus.simulate_accept(&router.wrap(), 1, &peer).unwrap();
+    assert_eq!(
+        res.issues,
+        vec![AcceptIssue::OfferedNotOwned {
+            token: offered,
+            owner: Some(creator.to_string()),
+        }]
+    );
+
+    // accepting fails with the error of the first issue
+    let err = router
+        .execute(peer.clone(), pegasus.accept_offer(1).unwrap())
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::UnauthorizedPeer {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID,
+            peer: sender.to_string(),
+        }
+    );
+
+    // the offer expires
+    setup_block_time(router, 1000 + MAX_EXPIRY);
+    let res = pegasus.simulate_accept(&router.wrap(), 1, &peer).unwrap();
+    assert_eq!(res.issues.len(), 2);
+    assert!(matches!(res.issues[0], AcceptIssue::Expired { .. }));
+}
+
+#[test]
+fn offers_health() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &sender, &creator, &collection_a, TOKEN3_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+    for token_id in [TOKEN1_ID, TOKEN3_ID] {
+        approve(
+            router,
+            &sender,
+            &collection_a,
+            &trade_contract,
+            token_id,
+            None,
+        );
+    }
+
+    for token_id in [TOKEN1_ID, TOKEN3_ID] {
+        let msg = pegasus
+            .create_offer(
+                vec![TokenMsg {
+                    collection: collection_a.to_string(),
+                    token_id,
+                }],
+                vec![TokenMsg {
+                    collection: collection_a.to_string(),
+                    token_id: TOKEN2_ID,
+                }],
+                &peer,
+                None,
+                None,
+            )
+            .unwrap();
+        router.execute(sender.clone(), msg).unwrap();
+    }
+
+    let res = pegasus.offer_status(&router.wrap(), 1).unwrap();
+    assert!(res.status.valid);
+    assert!(res.status.offered_nfts[0].owned && res.status.offered_nfts[0].approved);
+    // the peer hasn't approved yet, which doesn't invalidate the offer
+    assert!(res.status.wanted_nfts[0].owned && !res.status.wanted_nfts[0].approved);
+
+    // the sender moves the token of the first offer away
+    _transfer(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    let res = pegasus.offer_status(&router.wrap(), 1).unwrap();
+    assert!(!res.status.valid);
+    assert_eq!(res.status.offered_nfts[0].owner, Some(creator.to_string()));
+
+    let res = pegasus.offers_health(&router.wrap(), None, None).unwrap();
+    assert_eq!(
+        res.offers
+            .iter()
+            .map(|s| (s.id, s.valid))
+            .collect::<Vec<_>>(),
+        vec![(1, false), (2, true)]
+    );
+    let res = pegasus
+        .offers_health(&router.wrap(), Some(1), Some(1))
+        .unwrap();
+    assert_eq!(res.offers.len(), 1);
+    assert_eq!(res.offers[0].id, 2);
+
+    // all offers are invalid once expired
+    setup_block_time(router, 1000 + MAX_EXPIRY);
+    let res = pegasus.offers_health(&router.wrap(), None, None).unwrap();
+    assert!(res.offers.iter().all(|s| s.expired && !s.valid));
+}
+
+#[test]
+fn operator_approval() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+
+    let create_msg = pegasus
+        .create_offer(
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN1_ID,
+            }],
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN2_ID,
+            }],
+            &peer,
+            None,
+            None,
+        )
+        .unwrap();
+
+    // an operator approval ending before the offer expires isn't enough
+    approve_all(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        Some(Expiration::AtTime(Timestamp::from_seconds(1900))),
+    );
+    let err = router
+        .execute(sender.clone(), create_msg.clone())
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ApprovalExpiresBeforeOffer {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID,
+            approval_expires: Timestamp::from_seconds(1900),
+            offer_expires: Timestamp::from_seconds(1000 + MIN_EXPIRY + 1),
+        }
+    );
+
+    // both sides approve the contract as operator
+    approve_all(router, &sender, &collection_a, &trade_contract, None);
+    approve_all(router, &peer, &collection_a, &trade_contract, None);
+    router.execute(sender.clone(), create_msg).unwrap();
+
+    let res = pegasus.simulate_accept(&router.wrap(), 1, &peer).unwrap();
+    assert!(res.issues.is_empty());
+
+    router
+        .execute(peer.clone(), pegasus.accept_offer(1).unwrap())
+        .unwrap();
+    let res: OwnerOfResponse = router
+        .wrap()
+        .query_wasm_smart(
+            collection_a,
+            &Cw721QueryMsg::OwnerOf {
+                token_id: TOKEN1_ID.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.owner, peer.to_string());
+}
+
+#[test]
+fn approval_expiry() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &sender, &creator, &collection_a, TOKEN3_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+
+    let approval_expires = Timestamp::from_seconds(1000 + MIN_EXPIRY + 100);
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN1_ID,
+        Some(Expiration::AtTime(approval_expires)),
+    );
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN3_ID,
+        None,
+    );
+
+    let create_msg = |expires_at| {
+        pegasus
+            .create_offer(
+                vec![
+                    TokenMsg {
+                        collection: collection_a.to_string(),
+                        token_id: TOKEN3_ID,
+                    },
+                    TokenMsg {
+                        collection: collection_a.to_string(),
+                        token_id: TOKEN1_ID,
+                    },
+                ],
+                vec![TokenMsg {
+                    collection: collection_a.to_string(),
+                    token_id: TOKEN2_ID,
+                }],
+                &peer,
+                expires_at,
+                None,
+            )
+            .unwrap()
+    };
+
+    // an explicit expiry after the approval is rejected
+    let offer_expires = Timestamp::from_seconds(1000 + MAX_EXPIRY);
+    let err = router
+        .execute(sender.clone(), create_msg(Some(offer_expires)))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ApprovalExpiresBeforeOffer {
+            collection: collection_a.to_string(),
+            token_id: TOKEN1_ID,
+            approval_expires,
+            offer_expires,
+        }
+    );
+
+    // an expiry before the approval is accepted
+    let offer_expires = Timestamp::from_seconds(1000 + MIN_EXPIRY + 50);
+    router
+        .execute(sender.clone(), create_msg(Some(offer_expires)))
+        .unwrap();
+    let res = pegasus.offer(&router.wrap(), 1).unwrap();
+    assert_eq!(res.offer.unwrap().expires_at, offer_expires);
+
+    // height based approvals can't be checked against the offer expiry
+    mint_for(router, &sender, &creator, &collection_a, TOKEN4_ID);
+    let height = router.block_info().height + 1;
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN4_ID,
+        Some(Expiration::AtHeight(height)),
+    );
+    let create_msg = pegasus
+        .create_offer(
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN4_ID,
+            }],
+            vec![TokenMsg {
+                collection: collection_a.to_string(),
+                token_id: TOKEN2_ID,
+            }],
+            &peer,
+            None,
+            None,
+        )
+        .unwrap();
+    let err = router
+        .execute(sender.clone(), create_msg.clone())
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ApprovalExpiresAtHeight {
+            collection: collection_a.to_string(),
+            token_id: TOKEN4_ID,
+            height,
+        }
+    );
+
+    // a time based operator approval is preferred over the height based one
+    approve_all(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        Some(Expiration::AtTime(Timestamp::from_seconds(
+            1000 + MAX_EXPIRY,
+        ))),
+    );
+    router.execute(sender, create_msg).unwrap();
+}
+
+#[test]
+fn offer_memo() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+    approve(
+        router,
+        &sender,
+        &collection_a,
+        &trade_contract,
+        TOKEN1_ID,
+        None,
+    );
+
+    let create_msg = |memo: &str| {
+        pegasus
+            .create_offer(
+                vec![TokenMsg {
+                    collection: collection_a.to_string(),
+                    token_id: TOKEN1_ID,
+                }],
+                vec![TokenMsg {
+                    collection: collection_a.to_string(),
+                    token_id: TOKEN2_ID,
+                }],
+                &peer,
+                None,
+                Some(memo.to_string()),
+            )
+            .unwrap()
+    };
+
+    // memos are limited to max_memo_length
+    let err = router
+        .execute(sender.clone(), create_msg(&"a".repeat(65)))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MemoTooLong { max: 64 }
+    );
+
+    let res = router
+        .execute(sender.clone(), create_msg("swap for your rare?"))
+        .unwrap();
+    let res = res
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm-pegasus-create-offer")
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|a| a.key == "memo" && a.value == "swap for your rare?"));
+
+    let res = pegasus.offer(&router.wrap(), 1).unwrap();
+    assert_eq!(
+        res.offer.unwrap().memo,
+        Some("swap for your rare?".to_string())
+    );
+}
+
+#[test]
+fn collection_params() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, collection_b) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    for token_id in [TOKEN1_ID, TOKEN3_ID] {
+        mint_for(router, &sender, &creator, &collection_a, token_id);
+        approve(
+            router,
+            &sender,
+            &collection_a,
+            &trade_contract,
+            token_id,
+            None,
+        );
+    }
+    mint_for(router, &peer, &creator, &collection_b, TOKEN2_ID);
+
+    // collection a allows a single token per side & needs offers of at least two days
+    let offer_expiry = ExpiryRange::new(2 * MIN_EXPIRY, MAX_EXPIRY);
+    router
+        .wasm_sudo(
+            trade_contract.clone(),
+            &SudoMsg::SetCollectionParams {
+                collection: collection_a.to_string(),
+                max_tokens: Some(1),
+                offer_expiry: Some(offer_expiry.clone()),
+            },
+        )
+        .unwrap();
+    let res = pegasus
+        .collection_params(&router.wrap(), &collection_a)
+        .unwrap();
+    assert_eq!(res.params.offer_expiry, Some(offer_expiry));
+
+    let create_msg = |offered: Vec<u32>, expires_at: Option<u64>| {
+        pegasus
+            .create_offer(
+                offered
+                    .into_iter()
+                    .map(|token_id| TokenMsg {
+                        collection: collection_a.to_string(),
+                        token_id,
+                    })
+                    .collect(),
+                vec![TokenMsg {
+                    collection: collection_b.to_string(),
+                    token_id: TOKEN2_ID,
+                }],
+                &peer,
+                expires_at.map(Timestamp::from_seconds),
+                None,
+            )
+            .unwrap()
+    };
+
+    let err = router
+        .execute(sender.clone(), create_msg(vec![TOKEN1_ID, TOKEN3_ID], None))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MaxCollectionTokens {
+            collection: collection_a.to_string(),
+            limit: 1
+        }
+    );
+
+    // the global window allows a day, collection a doesn't
+    let err = router
+        .execute(
+            sender.clone(),
+            create_msg(vec![TOKEN1_ID], Some(1000 + MIN_EXPIRY + 1)),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ExpiryRange(crate::ExpiryRangeError::InvalidExpirationRange {})
+    );
+
+    // the default expiry is the earliest one valid for all collections
+    router
+        .execute(sender.clone(), create_msg(vec![TOKEN1_ID], None))
+        .unwrap();
+    let res = pegasus.offer(&router.wrap(), 1).unwrap();
+    assert_eq!(
+        res.offer.unwrap().expires_at,
+        Timestamp::from_seconds(1000 + 2 * MIN_EXPIRY + 1)
+    );
+
+    // without overrides the global params apply again
+    router
+        .wasm_sudo(
+            trade_contract,
+            &SudoMsg::RemoveCollectionParams {
+                collection: collection_a.to_string(),
+            },
+        )
+        .unwrap();
+    router
+        .execute(
+            sender,
+            create_msg(vec![TOKEN3_ID], Some(1000 + MIN_EXPIRY + 1)),
+        )
+        .unwrap();
+}
+
+#[test]
+fn fillable_offers() {
+    let router = &mut custom_mock_app();
+    setup_block_time(router, 1000);
+
+    let (sender, peer, creator) = setup_accounts(router).unwrap();
+    let (trade_contract, collection_a, _) = setup_contracts(router, &creator).unwrap();
+    let pegasus = PegasusContract(trade_contract.clone());
+
+    mint_for(router, &sender, &creator, &collection_a, TOKEN1_ID);
+    mint_for(router, &sender, &creator, &collection_a, TOKEN3_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN2_ID);
+    mint_for(router, &peer, &creator, &collection_a, TOKEN4_ID);
+    approve_all(router, &sender, &collection_a, &trade_contract, None);
+
+    // one offer for each token of the peer
+    for (offered, wanted) in [(TOKEN1_ID, TOKEN2_ID), (TOKEN3_ID, TOKEN4_ID)].iter() {
+        let msg = pegasus
+            .create_offer(
+                vec![TokenMsg {
+                    collection: collection_a.to_string(),
+                    token_id: *offered,
+                }],
+                vec![TokenMsg {
+                    collection: collection_a.to_string(),
+                    token_id: *wanted,
+                }],
+                &peer,
+                None,
+                None,
+            )
+            .unwrap();
+        router.execute(sender.clone(), msg).unwrap();
+    }
+
+    let fillable = |router: &StargazeApp, owner: &Addr, start_after, limit| {
+        pegasus
+            .fillable_offers(&router.wrap(), owner, &collection_a, start_after, limit)
+            .unwrap()
+    };
+    let ids = |res: &OffersResponse| res.offers.iter().map(|o| o.id).collect::<Vec<_>>();
+
+    let res = fillable(router, &peer, None, None);
+    assert_eq!(ids(&res), vec![1, 2]);
+
+    // page through the offers received by the peer
+    let res = fillable(router, &peer, None, Some(1));
+    assert_eq!(ids(&res), vec![1]);
+    let res = fillable(router, &peer, Some(1), Some(1));
+    assert_eq!(ids(&res), vec![2]);
+    assert!(fillable(router, &peer, Some(2), None).offers.is_empty());
+
+    // the sender owns tokens too, but can't accept its own offers
+    assert!(fillable(router, &sender, None, None).offers.is_empty());
+
+    // offers for tokens the peer no longer owns can't be filled
+    _transfer(router, &peer, &creator, &collection_a, TOKEN4_ID);
+    assert_eq!(ids(&fillable(router, &peer, None, None)), vec![1]);
+    assert!(fillable(router, &creator, None, None).offers.is_empty());
+}
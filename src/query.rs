@@ -1,10 +1,29 @@
-use crate::msg::{OfferResponse, OffersResponse, ParamsResponse};
-use crate::state::{offers, SUDO_PARAMS};
-use cosmwasm_std::{Addr, Deps, Order, StdResult};
+use crate::helpers::{check_accept, is_approved, token_owner};
+use crate::msg::{
+    AdminResponse, BlockedSendersResponse, CollectionParamsResponse, CollectionStatsResponse,
+    ConfigResponse, DepositsResponse, FilteredOffersResponse, InboxPreferencesResponse,
+    LimitBounds, OfferFilter, OfferFilterStatus, OfferResponse, OfferStatus, OfferStatusResponse,
+    OffersHealthResponse, OffersResponse, ParamsResponse, ReputationResponse,
+    SimulateAcceptResponse, StatsResponse, TokenInterestResponse, TokenStatus, UserStatsResponse,
+};
+use crate::state::{
+    deposits, offers, Offer, Token, TokenId, TradeSide, ADMIN, BLOCKED_SENDERS, COLLECTION_PARAMS,
+    COLLECTION_STATS, INBOX_PREFERENCES, MAX_BUNDLE_LIMIT, MAX_EXPIRY, MAX_INCOMING_OFFERS_LIMIT,
+    MAX_MEMO_LENGTH_LIMIT, MAX_OFFERS_LIMIT, MIN_BUNDLE_LIMIT, MIN_EXPIRY,
+    MIN_INCOMING_OFFERS_LIMIT, MIN_MEMO_LENGTH_LIMIT, MIN_OFFERS_LIMIT, OFFER_HOOKS, PENDING_ADMIN,
+    SUDO_PARAMS, TRADE_HISTORY, TRADE_STATS, USER_STATS,
+};
+use crate::ExpiryRange;
+use cosmwasm_std::{Addr, Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
+use sg_controllers::HooksResponse;
+use std::collections::{BTreeMap, BTreeSet};
 
 // Query limits
-// const DEFAULT_QUERY_LIMIT: u32 = 10;
-// const MAX_QUERY_LIMIT: u32 = 30;
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 30;
+// Maximum amount of offers a filtered query looks at
+const MAX_SCAN_LIMIT: usize = 300;
 
 pub fn query_offer(deps: Deps, id: u64) -> StdResult<OfferResponse> {
     let offer = offers().may_load(deps.storage, id)?;
@@ -16,6 +35,48 @@ pub fn query_params(deps: Deps) -> StdResult<ParamsResponse> {
     Ok(ParamsResponse { params })
 }
 
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let params = SUDO_PARAMS.load(deps.storage)?;
+    Ok(ConfigResponse {
+        offer_expiry: params.offer_expiry,
+        max_offers: params.max_offers,
+        max_incoming_offers: params.max_incoming_offers,
+        bundle_limit: params.bundle_limit,
+        max_memo_length: params.max_memo_length,
+        offer_expiry_bounds: ExpiryRange {
+            min: MIN_EXPIRY,
+            max: MAX_EXPIRY,
+        },
+        max_offers_bounds: LimitBounds {
+            min: MIN_OFFERS_LIMIT,
+            max: MAX_OFFERS_LIMIT,
+        },
+        max_incoming_offers_bounds: LimitBounds {
+            min: MIN_INCOMING_OFFERS_LIMIT,
+            max: MAX_INCOMING_OFFERS_LIMIT,
+        },
+        bundle_limit_bounds: LimitBounds {
+            min: MIN_BUNDLE_LIMIT,
+            max: MAX_BUNDLE_LIMIT,
+        },
+        max_memo_length_bounds: LimitBounds {
+            min: MIN_MEMO_LENGTH_LIMIT,
+            max: MAX_MEMO_LENGTH_LIMIT,
+        },
+    })
+}
+
+pub fn query_collection_params(
+    deps: Deps,
+    collection: Addr,
+) -> StdResult<CollectionParamsResponse> {
+    let params = COLLECTION_PARAMS
+        .may_load(deps.storage, &collection)?
+        .unwrap_or_default();
+
+    Ok(CollectionParamsResponse { params })
+}
+
 pub fn query_offers_by_sender(deps: Deps, sender: Addr) -> StdResult<OffersResponse> {
     let offers = offers()
         .idx
@@ -39,3 +100,416 @@ pub fn query_offers_by_peer(deps: Deps, peer: Addr) -> StdResult<OffersResponse>
 
     Ok(OffersResponse { offers })
 }
+
+pub fn query_offers_by_collection(
+    deps: Deps,
+    collection: Addr,
+    side: TradeSide,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let offers = offers()
+        .idx
+        .by_collection
+        .prefix(collection, side)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| offers().load(deps.storage, id?))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OffersResponse { offers })
+}
+
+pub fn query_token_interest(
+    deps: Deps,
+    env: Env,
+    collection: Addr,
+    token_id: TokenId,
+) -> StdResult<TokenInterestResponse> {
+    let mut open_offers = 0;
+    let mut offered_collections = BTreeSet::new();
+    let mut latest_offers = vec![];
+    let mut complete = true;
+
+    // newest offers first, ids are handed out in increasing order
+    let ids = offers()
+        .idx
+        .by_wanted_token
+        .prefix(collection, token_id)
+        .keys(deps.storage, None, None, Order::Descending);
+    for (scanned, id) in ids.enumerate() {
+        if scanned == MAX_SCAN_LIMIT {
+            complete = false;
+            break;
+        }
+        let offer = offers().load(deps.storage, id?)?;
+        if offer.is_expired(&env.block) {
+            continue;
+        }
+
+        open_offers += 1;
+        offered_collections.extend(offer.offered_nfts.into_iter().map(|t| t.collection));
+        if latest_offers.len() < DEFAULT_QUERY_LIMIT as usize {
+            latest_offers.push(offer.created_at);
+        }
+    }
+
+    Ok(TokenInterestResponse {
+        open_offers,
+        offered_collections: offered_collections.into_iter().collect(),
+        latest_offers,
+        complete,
+    })
+}
+
+/// Offers to `owner` that want NFTs of `collection`, and can be accepted since it still
+/// owns all wanted NFTs. Walks the offers received by the owner, which are bounded by
+/// max_incoming_offers, and pages through them by offer id.
+pub fn query_fillable_offers(
+    deps: Deps,
+    env: Env,
+    owner: Addr,
+    collection: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    // bundles can want the same NFT, so every owner is only looked up once
+    let mut owned: BTreeMap<(Addr, TokenId), bool> = BTreeMap::new();
+    let mut owns = |token: &Token| {
+        *owned
+            .entry((token.collection.clone(), token.token_id))
+            .or_insert_with(|| token_owner(&deps.querier, token).as_deref() == Some(owner.as_str()))
+    };
+
+    let mut fillable = vec![];
+    let received = offers().idx.by_peer.prefix(owner.clone()).range(
+        deps.storage,
+        start,
+        None,
+        Order::Ascending,
+    );
+    for item in received {
+        let (_, offer) = item?;
+        if offer.is_expired(&env.block)
+            || !offer.wanted_nfts.iter().any(|t| t.collection == collection)
+        {
+            continue;
+        }
+        if offer.wanted_nfts.iter().all(&mut owns) {
+            fillable.push(offer);
+            if fillable.len() == limit {
+                break;
+            }
+        }
+    }
+
+    Ok(OffersResponse { offers: fillable })
+}
+
+pub fn query_offers(
+    deps: Deps,
+    env: Env,
+    filter: OfferFilter,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<FilteredOffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let api = deps.api;
+
+    let OfferFilter {
+        sender,
+        peer,
+        collection,
+        status,
+        expires_before,
+    } = filter;
+    let sender = sender.map(|s| api.addr_validate(&s)).transpose()?;
+    let peer = peer.map(|p| api.addr_validate(&p)).transpose()?;
+    let collection = collection.map(|c| api.addr_validate(&c)).transpose()?;
+
+    // use the most selective index, the other criteria are checked per offer
+    let candidates = match (&sender, &peer) {
+        (Some(sender), _) => offers().idx.by_sender.prefix(sender.clone()).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        (None, Some(peer)) => offers().idx.by_peer.prefix(peer.clone()).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        (None, None) => match &collection {
+            Some(collection) => {
+                let ids = |side| {
+                    offers()
+                        .idx
+                        .by_collection
+                        .prefix(collection.clone(), side)
+                        .keys(
+                            deps.storage,
+                            start_after.map(Bound::exclusive),
+                            None,
+                            Order::Ascending,
+                        )
+                };
+                Box::new(
+                    merge_ids(ids(TradeSide::Offered), ids(TradeSide::Wanted)).map(|id| {
+                        let id = id?;
+                        Ok((id, offers().load(deps.storage, id)?))
+                    }),
+                )
+            }
+            None => offers().range(deps.storage, start, None, Order::Ascending),
+        },
+    };
+
+    let matches = |offer: &Offer| {
+        sender
+            .as_ref()
+            .map_or(true, |sender| offer.sender == *sender)
+            && peer.as_ref().map_or(true, |peer| offer.peer == *peer)
+            && collection.as_ref().map_or(true, |collection| {
+                offer
+                    .offered_nfts
+                    .iter()
+                    .chain(offer.wanted_nfts.iter())
+                    .any(|token| token.collection == *collection)
+            })
+            && status.as_ref().map_or(true, |status| {
+                offer.is_expired(&env.block) == (*status == OfferFilterStatus::Expired)
+            })
+            && expires_before.map_or(true, |expires_before| offer.expires_at < expires_before)
+    };
+
+    let mut offers = vec![];
+    let mut last_scanned = None;
+    for (scanned, item) in candidates.enumerate() {
+        let (id, offer) = item?;
+        if matches(&offer) {
+            offers.push(offer);
+        }
+        if offers.len() == limit || scanned + 1 == MAX_SCAN_LIMIT {
+            last_scanned = Some(id);
+            break;
+        }
+    }
+
+    Ok(FilteredOffersResponse {
+        offers,
+        last_scanned,
+    })
+}
+
+/// Merges two ascending id iterators into one, ids present in both are returned once
+fn merge_ids<'a>(
+    a: Box<dyn Iterator<Item = StdResult<u64>> + 'a>,
+    b: Box<dyn Iterator<Item = StdResult<u64>> + 'a>,
+) -> impl Iterator<Item = StdResult<u64>> + 'a {
+    let mut a = a.peekable();
+    let mut b = b.peekable();
+    std::iter::from_fn(move || {
+        let next_a = match a.peek() {
+            Some(Ok(id)) => Some(*id),
+            Some(Err(_)) => return a.next(),
+            None => None,
+        };
+        let next_b = match b.peek() {
+            Some(Ok(id)) => Some(*id),
+            Some(Err(_)) => return b.next(),
+            None => None,
+        };
+        match (next_a, next_b) {
+            (Some(id_a), Some(id_b)) if id_a == id_b => {
+                b.next();
+                a.next()
+            }
+            (Some(id_a), Some(id_b)) if id_a < id_b => a.next(),
+            (Some(_), None) => a.next(),
+            (_, Some(_)) => b.next(),
+            (None, None) => None,
+        }
+    })
+}
+
+pub fn query_user_stats(deps: Deps, address: Addr) -> StdResult<UserStatsResponse> {
+    let stats = USER_STATS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    Ok(UserStatsResponse { stats })
+}
+
+pub fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let stats = TRADE_STATS.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(StatsResponse { stats })
+}
+
+pub fn query_collection_stats(deps: Deps, collection: Addr) -> StdResult<CollectionStatsResponse> {
+    let stats = COLLECTION_STATS
+        .may_load(deps.storage, &collection)?
+        .unwrap_or_default();
+
+    Ok(CollectionStatsResponse { stats })
+}
+
+pub fn query_reputation(deps: Deps, address: Addr) -> StdResult<ReputationResponse> {
+    let history = TRADE_HISTORY
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    Ok(ReputationResponse {
+        reputation: history.reputation(),
+        history,
+    })
+}
+
+pub fn query_inbox_preferences(deps: Deps, address: Addr) -> StdResult<InboxPreferencesResponse> {
+    let preferences = INBOX_PREFERENCES
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    Ok(InboxPreferencesResponse { preferences })
+}
+
+pub fn query_deposits(
+    deps: Deps,
+    owner: Addr,
+    start_after: Option<Token>,
+    limit: Option<u32>,
+) -> StdResult<DepositsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.map(|token| Bound::exclusive((token.collection, token.token_id)));
+
+    let tokens = deposits()
+        .idx
+        .owner
+        .prefix(owner)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| {
+            key.map(|(collection, token_id)| Token {
+                collection,
+                token_id,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DepositsResponse { deposits: tokens })
+}
+
+pub fn query_blocked_senders(deps: Deps, owner: Addr) -> StdResult<BlockedSendersResponse> {
+    let senders = BLOCKED_SENDERS
+        .prefix(&owner)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BlockedSendersResponse { senders })
+}
+
+pub fn query_offer_hooks(deps: Deps) -> StdResult<HooksResponse> {
+    OFFER_HOOKS.query_hooks(deps)
+}
+
+pub fn query_admin(deps: Deps) -> StdResult<AdminResponse> {
+    Ok(AdminResponse {
+        admin: ADMIN.may_load(deps.storage)?,
+        pending_admin: PENDING_ADMIN.may_load(deps.storage)?,
+    })
+}
+
+pub fn query_simulate_accept(
+    deps: Deps,
+    env: Env,
+    id: u64,
+    accepter: Addr,
+) -> StdResult<SimulateAcceptResponse> {
+    let offer = offers().load(deps.storage, id)?;
+    let issues = check_accept(deps, &env, &offer, &accepter);
+
+    Ok(SimulateAcceptResponse { issues })
+}
+
+pub fn query_offer_status(deps: Deps, env: Env, id: u64) -> StdResult<OfferStatusResponse> {
+    let offer = offers().load(deps.storage, id)?;
+    let status = offer_status(deps, &env, &offer);
+
+    Ok(OfferStatusResponse { status })
+}
+
+pub fn query_offers_health(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OffersHealthResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let offers = offers()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, offer)| offer_status(deps, &env, &offer)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OffersHealthResponse { offers })
+}
+
+/// Re-checks ownership and approval of every token in the offer
+fn offer_status(deps: Deps, env: &Env, offer: &Offer) -> OfferStatus {
+    let token_status = |token: &Token, expected_owner: &Addr| {
+        let owner = token_owner(&deps.querier, token);
+        TokenStatus {
+            token: token.clone(),
+            owned: owner.as_deref() == Some(expected_owner.as_str()),
+            approved: owner.as_deref().map_or(false, |owner| {
+                is_approved(&deps.querier, token, owner, &env.contract.address)
+            }),
+            owner,
+        }
+    };
+
+    let offered_nfts = offer
+        .offered_nfts
+        .iter()
+        .map(|token| {
+            if offer.escrowed {
+                // escrowed tokens are held by the contract itself
+                TokenStatus {
+                    approved: true,
+                    ..token_status(token, &env.contract.address)
+                }
+            } else {
+                token_status(token, &offer.sender)
+            }
+        })
+        .collect::<Vec<_>>();
+    let wanted_nfts = offer
+        .wanted_nfts
+        .iter()
+        .map(|token| token_status(token, &offer.peer))
+        .collect::<Vec<_>>();
+
+    let expired = offer.is_expired(&env.block);
+    let valid = !expired
+        && offered_nfts.iter().all(|t| t.owned && t.approved)
+        && wanted_nfts.iter().all(|t| t.owned);
+
+    OfferStatus {
+        id: offer.id,
+        expired,
+        offered_nfts,
+        wanted_nfts,
+        valid,
+    }
+}
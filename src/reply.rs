@@ -0,0 +1,53 @@
+use crate::error::ContractError;
+use crate::state::PENDING_TRANSFERS;
+use cosmwasm_std::{DepsMut, Env, Reply};
+use cw721_base::helpers::Cw721Contract;
+use sg_std::Response;
+
+pub const OFFER_HOOK_REPLY_ID: u64 = 1;
+// Reply ids from here on point to a transfer in PENDING_TRANSFERS
+pub const TRANSFER_REPLY_ID: u64 = 1_000;
+
+/// A failing offer hook is reported but never blocks the offer lifecycle
+pub fn reply_offer_hook(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let mut res = Response::new().add_attribute("action", "offer_hook");
+
+    if let Err(err) = msg.result.into_result() {
+        res = res.add_attribute("offer_hook_error", err);
+    }
+
+    Ok(res)
+}
+
+/// Reports which NFT transfer failed, reverting the whole settlement. After the last
+/// transfer, verifies that every recipient actually owns its NFT.
+pub fn reply_transfer(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let transfers = PENDING_TRANSFERS.load(deps.storage)?;
+    let transfer = transfers
+        .get((msg.id - TRANSFER_REPLY_ID) as usize)
+        .ok_or(ContractError::UnknownReplyId { id: msg.id })?;
+
+    if msg.result.is_err() {
+        return Err(ContractError::TransferFailed {
+            collection: transfer.token.collection.to_string(),
+            token_id: transfer.token.token_id,
+        });
+    }
+
+    // a cw721 contract could accept a TransferNft without moving the token
+    for transfer in transfers.iter() {
+        let owner = Cw721Contract(transfer.token.collection.clone())
+            .owner_of(&deps.querier, transfer.token.token_id.to_string(), false)?
+            .owner;
+        if owner != transfer.recipient {
+            return Err(ContractError::OwnershipNotTransferred {
+                collection: transfer.token.collection.to_string(),
+                token_id: transfer.token.token_id,
+                recipient: transfer.recipient.to_string(),
+            });
+        }
+    }
+    PENDING_TRANSFERS.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "verify_transfers"))
+}
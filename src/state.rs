@@ -1,13 +1,30 @@
-use cosmwasm_std::{Addr, StdResult, Storage, Timestamp};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex, UniqueIndex};
+use cosmwasm_std::{
+    Addr, BlockInfo, Decimal, Empty, Order, StdError, StdResult, Storage, Timestamp,
+};
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, Prefix, UniqueIndex,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sg_controllers::Hooks;
+use std::collections::BTreeMap;
 
 use crate::helpers::ExpiryRange;
 
 pub const MIN_EXPIRY: u64 = 3600 * 24; // seconds -> one day
 pub const MAX_EXPIRY: u64 = 3600 * 24 * 28; // seconds -> one month
 
+// Bounds of the limits, so offers can always be created and loops over them stay cheap
+pub const MIN_OFFERS_LIMIT: u64 = 1;
+pub const MAX_OFFERS_LIMIT: u64 = 100;
+pub const MIN_BUNDLE_LIMIT: u64 = 1;
+pub const MAX_BUNDLE_LIMIT: u64 = 20;
+pub const MIN_INCOMING_OFFERS_LIMIT: u64 = 1;
+pub const MAX_INCOMING_OFFERS_LIMIT: u64 = 100;
+// Memos are disabled at 0, the upper bound keeps offers small in storage
+pub const MIN_MEMO_LENGTH_LIMIT: u64 = 0;
+pub const MAX_MEMO_LENGTH_LIMIT: u64 = 256;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct SudoParams {
     /// Valid time range for Offers
@@ -20,12 +37,72 @@ pub struct SudoParams {
     /// Maximum amount of offers a user can send
     pub max_offers: u64,
 
+    /// Maximum amount of offers a user can receive
+    #[serde(default = "default_max_incoming_offers")]
+    pub max_incoming_offers: u64,
+
     /// Maximum amount of NFTs in bundle
     pub bundle_limit: u64,
+
+    /// Maximum length of an offer memo in bytes, params stored before memos existed
+    /// don't allow memos until governance sets a length
+    #[serde(default)]
+    pub max_memo_length: u64,
 }
 
 pub const SUDO_PARAMS: Item<SudoParams> = Item::new("sudo-params");
 
+// Params stored before the incoming limit existed get the loosest limit
+fn default_max_incoming_offers() -> u64 {
+    MAX_INCOMING_OFFERS_LIMIT
+}
+
+// Address that can update a subset of the params without a governance proposal
+pub const ADMIN: Item<Addr> = Item::new("admin");
+
+// Proposed admin, only becomes the admin after accepting
+pub const PENDING_ADMIN: Item<Addr> = Item::new("pending-admin");
+
+// Contracts that get notified of offer lifecycle events
+pub const OFFER_HOOKS: Hooks = Hooks::new("offer-hooks");
+
+/// Trade params of a single collection, unset fields fall back to the global params
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CollectionParams {
+    /// Maximum amount of NFTs of this collection on each side of an offer
+    pub max_tokens: Option<u64>,
+
+    /// Valid time range for offers with NFTs of this collection
+    pub offer_expiry: Option<ExpiryRange>,
+}
+
+pub const COLLECTION_PARAMS: Map<&Addr, CollectionParams> = Map::new("collection-params");
+
+/// Restrictions a peer puts on the offers it receives
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct InboxPreferences {
+    /// If set, only these senders can send offers to this peer
+    pub allowed_senders: Option<Vec<Addr>>,
+
+    /// Minimum amount of NFTs an offer to this peer has to contain
+    pub min_offered_nfts: u64,
+}
+
+impl InboxPreferences {
+    /// Checks if the given sender is allowed to send offers to this peer
+    pub fn accepts_sender(&self, sender: &Addr) -> bool {
+        match &self.allowed_senders {
+            Some(allowed_senders) => allowed_senders.contains(sender),
+            None => true,
+        }
+    }
+}
+
+pub const INBOX_PREFERENCES: Map<&Addr, InboxPreferences> = Map::new("inbox_preferences");
+
+// Senders blocked by a peer, keyed by (peer, sender)
+pub const BLOCKED_SENDERS: Map<(&Addr, &Addr), Empty> = Map::new("blocked_senders");
+
 pub type TokenId = u32;
 
 /// Represents a token that can be offered
@@ -49,8 +126,72 @@ pub struct Offer {
     pub peer: Addr,
     pub created_at: Timestamp,
     pub expires_at: Timestamp,
+
+    /// Whether the offered NFTs are held in escrow by the contract
+    #[serde(default)]
+    pub escrowed: bool,
+    /// Short note from the sender to the peer
+    #[serde(default)]
+    pub memo: Option<String>,
+}
+
+impl Offer {
+    /// An offer is expired once the block time reaches `expires_at`
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        block.time >= self.expires_at
+    }
+}
+
+/// NFT transfer dispatched by the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Transfer {
+    pub token: Token,
+    pub recipient: Addr,
+}
+
+pub struct DepositIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, Addr, (Addr, TokenId)>,
 }
 
+impl<'a> IndexList<Addr> for DepositIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Addr>> + '_> {
+        let v: Vec<&dyn Index<Addr>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+// NFTs sent to the contract with cw721 `SendNft`, keyed by (collection, token_id) and
+// credited to the owner reported by the collection. A collection can only report
+// the owners of its own tokens, so nobody can credit themselves the NFTs of another
+// collection. Deposits are moved into escrowed offers and credited back when such an
+// offer closes without a trade.
+pub fn deposits<'a>() -> IndexedMap<'a, (Addr, TokenId), Addr, DepositIndexes<'a>> {
+    let indexes = DepositIndexes {
+        owner: MultiIndex::new(|owner: &Addr| owner.clone(), "deposits", "deposits__owner"),
+    };
+    IndexedMap::new("deposits", indexes)
+}
+
+// Credit the NFTs of an escrowed offer back to its sender, they stay with the contract
+// until the sender withdraws them
+pub fn return_deposits(store: &mut dyn Storage, offer: &Offer) -> StdResult<()> {
+    if !offer.escrowed {
+        return Ok(());
+    }
+    for token in offer.offered_nfts.iter() {
+        deposits().save(
+            store,
+            (token.collection.clone(), token.token_id),
+            &offer.sender,
+        )?;
+    }
+
+    Ok(())
+}
+
+// Transfers dispatched by the last settlement, used to identify a failing transfer in `reply`
+pub const PENDING_TRANSFERS: Item<Vec<Transfer>> = Item::new("pending_transfers");
+
 // Incrementing ID counter
 pub const OFFER_ID_COUNTER: Item<u64> = Item::new("offer_id_counter");
 
@@ -63,15 +204,129 @@ pub fn next_offer_id(store: &mut dyn Storage) -> StdResult<u64> {
 }
 
 pub const OFFER_NAMESPACE: &str = "offers";
+/// Side of an offer an NFT is on
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeSide {
+    Offered,
+    Wanted,
+}
+
+/// Indexes offers by the collections of their NFTs. An offer gets an entry for every
+/// collection on each side, which a MultiIndex with a single key per offer can't do.
+pub struct CollectionIndex<'a> {
+    entries: Map<'a, (Addr, u8, u64), Empty>,
+}
+
+impl<'a> CollectionIndex<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        CollectionIndex {
+            entries: Map::new(namespace),
+        }
+    }
+
+    /// Ids of the offers with NFTs of `collection` on the given side
+    pub fn prefix(&self, collection: Addr, side: TradeSide) -> Prefix<u64, Empty, u64> {
+        self.entries.prefix((collection, side as u8))
+    }
+
+    fn keys(offer: &Offer) -> Vec<(Addr, u8, u64)> {
+        let side_keys = |nfts: &[Token], side: TradeSide| {
+            nfts.iter()
+                .map(move |token| (token.collection.clone(), side as u8, offer.id))
+                .collect::<Vec<_>>()
+        };
+        let mut keys = [
+            side_keys(&offer.offered_nfts, TradeSide::Offered),
+            side_keys(&offer.wanted_nfts, TradeSide::Wanted),
+        ]
+        .concat();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+}
+
+impl<'a> Index<Offer> for CollectionIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &Offer) -> StdResult<()> {
+        for key in Self::keys(data) {
+            self.entries.save(store, key, &Empty {})?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &Offer) -> StdResult<()> {
+        for key in Self::keys(old_data) {
+            self.entries.remove(store, key);
+        }
+        Ok(())
+    }
+}
+
+/// Indexes offers by the NFTs on one side, an offer gets an entry for every NFT on that side
+pub struct TokenIndex<'a> {
+    entries: Map<'a, (Addr, TokenId, u64), Empty>,
+    side: TradeSide,
+}
+
+impl<'a> TokenIndex<'a> {
+    pub const fn new(namespace: &'a str, side: TradeSide) -> Self {
+        TokenIndex {
+            entries: Map::new(namespace),
+            side,
+        }
+    }
+
+    /// Ids of the offers with the given NFT on the side of this index
+    pub fn prefix(&self, collection: Addr, token_id: TokenId) -> Prefix<u64, Empty, u64> {
+        self.entries.prefix((collection, token_id))
+    }
+
+    fn keys<'o>(&self, offer: &'o Offer) -> impl Iterator<Item = (Addr, TokenId, u64)> + 'o {
+        let nfts = match self.side {
+            TradeSide::Offered => &offer.offered_nfts,
+            TradeSide::Wanted => &offer.wanted_nfts,
+        };
+        nfts.iter()
+            .map(move |token| (token.collection.clone(), token.token_id, offer.id))
+    }
+}
+
+impl<'a> Index<Offer> for TokenIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &Offer) -> StdResult<()> {
+        for key in self.keys(data) {
+            self.entries.save(store, key, &Empty {})?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &Offer) -> StdResult<()> {
+        for key in self.keys(old_data) {
+            self.entries.remove(store, key);
+        }
+        Ok(())
+    }
+}
+
 pub struct OfferIndexes<'a> {
     pub id: UniqueIndex<'a, u64, Offer>,
     pub by_sender: MultiIndex<'a, Addr, Offer, u64>,
     pub by_peer: MultiIndex<'a, Addr, Offer, u64>,
+    pub by_collection: CollectionIndex<'a>,
+    pub by_offered_token: TokenIndex<'a>,
+    pub by_wanted_token: TokenIndex<'a>,
 }
 
 impl<'a> IndexList<Offer> for OfferIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Offer>> + '_> {
-        let v: Vec<&dyn Index<Offer>> = vec![&self.id, &self.by_sender, &self.by_peer];
+        let v: Vec<&dyn Index<Offer>> = vec![
+            &self.id,
+            &self.by_sender,
+            &self.by_peer,
+            &self.by_collection,
+            &self.by_offered_token,
+            &self.by_wanted_token,
+        ];
         Box::new(v.into_iter())
     }
 }
@@ -82,6 +337,189 @@ pub fn offers<'a>() -> IndexedMap<'a, u64, Offer, OfferIndexes<'a>> {
         id: UniqueIndex::new(|d| d.id, "offers__id"),
         by_sender: MultiIndex::new(|d| d.sender.clone(), "offers", "offers__sender"),
         by_peer: MultiIndex::new(|d| d.peer.clone(), "offers", "offers__peer"),
+        by_collection: CollectionIndex::new("offers__collection"),
+        by_offered_token: TokenIndex::new("offers__offered_token", TradeSide::Offered),
+        by_wanted_token: TokenIndex::new("offers__wanted_token", TradeSide::Wanted),
     };
     IndexedMap::new(OFFER_NAMESPACE, indexes)
 }
+
+/// Number of open offers sent and received by an address
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct UserStats {
+    pub offers_sent: u64,
+    pub offers_received: u64,
+}
+
+pub const USER_STATS: Map<&Addr, UserStats> = Map::new("user_stats");
+
+// Count a newly created offer for its sender and peer
+pub fn add_offer_to_stats(store: &mut dyn Storage, offer: &Offer) -> StdResult<()> {
+    USER_STATS.update(store, &offer.sender, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.offers_sent += 1;
+        Ok(stats)
+    })?;
+    USER_STATS.update(store, &offer.peer, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.offers_received += 1;
+        Ok(stats)
+    })?;
+
+    Ok(())
+}
+
+// Uncount an offer that got accepted, rejected or removed
+pub fn remove_offer_from_stats(store: &mut dyn Storage, offer: &Offer) -> StdResult<()> {
+    USER_STATS.update(store, &offer.sender, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.offers_sent = stats
+            .offers_sent
+            .checked_sub(1)
+            .ok_or_else(|| StdError::generic_err("Open offer count of sender underflowed"))?;
+        Ok(stats)
+    })?;
+    USER_STATS.update(store, &offer.peer, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.offers_received = stats
+            .offers_received
+            .checked_sub(1)
+            .ok_or_else(|| StdError::generic_err("Open offer count of peer underflowed"))?;
+        Ok(stats)
+    })?;
+
+    Ok(())
+}
+
+// Id of the next offer to rebuild, only set while offers stored by an older version
+// still have to be rebuilt
+pub const OFFERS_REBUILD: Item<u64> = Item::new("offers_rebuild");
+
+// Re-save the next `limit` stored offers and count them as open offers of their
+// addresses. Offers stored by older versions are missing from newer indexes and from
+// the user stats. Returns whether every offer has been rebuilt.
+pub fn rebuild_offers(store: &mut dyn Storage, limit: usize) -> StdResult<bool> {
+    let start = match OFFERS_REBUILD.may_load(store)? {
+        Some(start) => start,
+        None => return Ok(true),
+    };
+
+    let mut batch = offers()
+        .range(store, Some(Bound::inclusive(start)), None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let next = if batch.len() > limit {
+        batch.pop().map(|(id, _)| id)
+    } else {
+        None
+    };
+
+    for (id, offer) in batch.iter() {
+        offers().save(store, *id, offer)?;
+        add_offer_to_stats(store, offer)?;
+    }
+
+    match next {
+        Some(id) => OFFERS_REBUILD.save(store, &id)?,
+        None => OFFERS_REBUILD.remove(store),
+    }
+    Ok(next.is_none())
+}
+
+/// Lifetime counters of the offers handled by the contract
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TradeStats {
+    pub offers_created: u64,
+    pub offers_accepted: u64,
+    pub offers_rejected: u64,
+    /// Offers revoked by their sender
+    pub offers_removed: u64,
+    /// Offers removed by the maintainer after they expired
+    pub offers_expired: u64,
+}
+
+pub const TRADE_STATS: Item<TradeStats> = Item::new("trade_stats");
+
+/// Lifetime counters of the accepted offers involving a collection
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CollectionStats {
+    /// Accepted offers with at least one NFT of this collection
+    pub trades: u64,
+    /// NFTs of this collection that changed hands
+    pub tokens_traded: u64,
+}
+
+pub const COLLECTION_STATS: Map<&Addr, CollectionStats> = Map::new("collection_stats");
+
+// Apply a change to the lifetime offer counters
+pub fn update_trade_stats(
+    store: &mut dyn Storage,
+    update: impl FnOnce(&mut TradeStats),
+) -> StdResult<()> {
+    let mut stats = TRADE_STATS.may_load(store)?.unwrap_or_default();
+    update(&mut stats);
+    TRADE_STATS.save(store, &stats)
+}
+
+// Count an accepted offer for every collection it traded
+pub fn add_trade_to_collection_stats(store: &mut dyn Storage, offer: &Offer) -> StdResult<()> {
+    let mut traded: BTreeMap<&Addr, u64> = BTreeMap::new();
+    for token in offer.offered_nfts.iter().chain(offer.wanted_nfts.iter()) {
+        *traded.entry(&token.collection).or_default() += 1;
+    }
+
+    for (collection, tokens) in traded {
+        COLLECTION_STATS.update(store, collection, |stats| -> StdResult<_> {
+            let mut stats = stats.unwrap_or_default();
+            stats.trades += 1;
+            stats.tokens_traded += tokens;
+            Ok(stats)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Outcomes of the closed offers an address took part in
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TradeHistory {
+    /// Accepted offers, as sender or peer
+    pub trades_completed: u64,
+    /// Offers sent by this address that the peer rejected
+    pub rejections_received: u64,
+    /// Offers this address revoked
+    pub offers_cancelled: u64,
+    /// Offers sent by this address that expired before being accepted
+    pub offers_expired: u64,
+}
+
+impl TradeHistory {
+    /// Share of closed offers that ended in a trade, unset without any closed offers
+    pub fn reputation(&self) -> Option<Decimal> {
+        let closed = self.trades_completed
+            + self.rejections_received
+            + self.offers_cancelled
+            + self.offers_expired;
+        if closed == 0 {
+            return None;
+        }
+        Some(Decimal::from_ratio(self.trades_completed, closed))
+    }
+}
+
+pub const TRADE_HISTORY: Map<&Addr, TradeHistory> = Map::new("trade_history");
+
+// Apply a change to the trade history of an address
+pub fn update_trade_history(
+    store: &mut dyn Storage,
+    address: &Addr,
+    update: impl FnOnce(&mut TradeHistory),
+) -> StdResult<()> {
+    TRADE_HISTORY.update(store, address, |history| -> StdResult<_> {
+        let mut history = history.unwrap_or_default();
+        update(&mut history);
+        Ok(history)
+    })?;
+
+    Ok(())
+}
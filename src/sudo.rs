@@ -1,15 +1,22 @@
 use crate::helpers::ExpiryRange;
-use crate::state::{MAX_EXPIRY, SUDO_PARAMS};
+use crate::state::{
+    CollectionParams, SudoParams, ADMIN, COLLECTION_PARAMS, MAX_BUNDLE_LIMIT, MAX_EXPIRY,
+    MAX_INCOMING_OFFERS_LIMIT, MAX_MEMO_LENGTH_LIMIT, MAX_OFFERS_LIMIT, MIN_BUNDLE_LIMIT,
+    MIN_INCOMING_OFFERS_LIMIT, MIN_MEMO_LENGTH_LIMIT, MIN_OFFERS_LIMIT, OFFER_HOOKS, PENDING_ADMIN,
+    SUDO_PARAMS,
+};
 use crate::ExpiryRangeError;
 use crate::{error::ContractError, state::MIN_EXPIRY};
-use cosmwasm_std::{DepsMut, Env};
+use cosmwasm_std::{Addr, DepsMut, Env};
 use sg_std::Response;
 
 pub struct ParamInfo {
     pub offer_expiry: Option<ExpiryRange>,
     pub maintainer: Option<String>,
     pub max_offers: Option<u64>,
+    pub max_incoming_offers: Option<u64>,
     pub bundle_limit: Option<u64>,
+    pub max_memo_length: Option<u64>,
 }
 
 /// Only governance can update contract params
@@ -22,25 +29,15 @@ pub fn sudo_update_params(
         offer_expiry,
         maintainer,
         max_offers,
+        max_incoming_offers,
         bundle_limit,
+        max_memo_length,
     } = param_info;
 
     let mut params = SUDO_PARAMS.load(deps.storage)?;
 
     if let Some(offer_expiry) = offer_expiry {
-        offer_expiry.validate()?;
-
-        if offer_expiry.min < MIN_EXPIRY {
-            return Err(ContractError::ExpiryRange(
-                ExpiryRangeError::InvalidExpirationRange {},
-            ));
-        }
-        if offer_expiry.max > MAX_EXPIRY {
-            return Err(ContractError::ExpiryRange(
-                ExpiryRangeError::InvalidExpirationRange {},
-            ));
-        }
-
+        validate_expiry_range(&offer_expiry)?;
         params.offer_expiry = offer_expiry;
     }
 
@@ -52,11 +49,150 @@ pub fn sudo_update_params(
         params.max_offers = max_offers
     }
 
+    if let Some(max_incoming_offers) = max_incoming_offers {
+        params.max_incoming_offers = max_incoming_offers
+    }
+
     if let Some(bundle_limit) = bundle_limit {
         params.bundle_limit = bundle_limit
     }
 
+    if let Some(max_memo_length) = max_memo_length {
+        params.max_memo_length = max_memo_length
+    }
+
+    validate_limits(&params)?;
     SUDO_PARAMS.save(deps.storage, &params)?;
 
     Ok(Response::new().add_attribute("action", "update_params"))
 }
+
+/// Checks an expiry range is valid and within MIN_EXPIRY & MAX_EXPIRY
+pub fn validate_expiry_range(offer_expiry: &ExpiryRange) -> Result<(), ContractError> {
+    offer_expiry.validate()?;
+
+    if offer_expiry.min < MIN_EXPIRY || offer_expiry.max > MAX_EXPIRY {
+        return Err(ContractError::ExpiryRange(
+            ExpiryRangeError::InvalidExpirationRange {},
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks the limits are within their hard bounds
+pub fn validate_limits(params: &SudoParams) -> Result<(), ContractError> {
+    if !(MIN_OFFERS_LIMIT..=MAX_OFFERS_LIMIT).contains(&params.max_offers) {
+        return Err(ContractError::InvalidMaxOffers {
+            max_offers: params.max_offers,
+            min: MIN_OFFERS_LIMIT,
+            max: MAX_OFFERS_LIMIT,
+        });
+    }
+    if !(MIN_INCOMING_OFFERS_LIMIT..=MAX_INCOMING_OFFERS_LIMIT)
+        .contains(&params.max_incoming_offers)
+    {
+        return Err(ContractError::InvalidMaxIncomingOffers {
+            max_incoming_offers: params.max_incoming_offers,
+            min: MIN_INCOMING_OFFERS_LIMIT,
+            max: MAX_INCOMING_OFFERS_LIMIT,
+        });
+    }
+    if !(MIN_BUNDLE_LIMIT..=MAX_BUNDLE_LIMIT).contains(&params.bundle_limit) {
+        return Err(ContractError::InvalidBundleLimit {
+            bundle_limit: params.bundle_limit,
+            min: MIN_BUNDLE_LIMIT,
+            max: MAX_BUNDLE_LIMIT,
+        });
+    }
+    if !(MIN_MEMO_LENGTH_LIMIT..=MAX_MEMO_LENGTH_LIMIT).contains(&params.max_memo_length) {
+        return Err(ContractError::InvalidMaxMemoLength {
+            max_memo_length: params.max_memo_length,
+            min: MIN_MEMO_LENGTH_LIMIT,
+            max: MAX_MEMO_LENGTH_LIMIT,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn sudo_set_collection_params(
+    deps: DepsMut,
+    _env: Env,
+    collection: Addr,
+    params: CollectionParams,
+) -> Result<Response, ContractError> {
+    if let Some(offer_expiry) = &params.offer_expiry {
+        validate_expiry_range(offer_expiry)?;
+    }
+    if let Some(max_tokens) = params.max_tokens {
+        if !(MIN_BUNDLE_LIMIT..=MAX_BUNDLE_LIMIT).contains(&max_tokens) {
+            return Err(ContractError::InvalidBundleLimit {
+                bundle_limit: max_tokens,
+                min: MIN_BUNDLE_LIMIT,
+                max: MAX_BUNDLE_LIMIT,
+            });
+        }
+    }
+
+    COLLECTION_PARAMS.save(deps.storage, &collection, &params)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_collection_params")
+        .add_attribute("collection", collection))
+}
+
+pub fn sudo_remove_collection_params(
+    deps: DepsMut,
+    _env: Env,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    COLLECTION_PARAMS.remove(deps.storage, &collection);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_collection_params")
+        .add_attribute("collection", collection))
+}
+
+pub fn sudo_add_offer_hook(
+    deps: DepsMut,
+    _env: Env,
+    hook: Addr,
+) -> Result<Response, ContractError> {
+    OFFER_HOOKS.add_hook(deps.storage, hook.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_offer_hook")
+        .add_attribute("hook", hook))
+}
+
+pub fn sudo_remove_offer_hook(
+    deps: DepsMut,
+    _env: Env,
+    hook: Addr,
+) -> Result<Response, ContractError> {
+    OFFER_HOOKS.remove_hook(deps.storage, hook.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_offer_hook")
+        .add_attribute("hook", hook))
+}
+
+pub fn sudo_update_admin(
+    deps: DepsMut,
+    _env: Env,
+    admin: Option<Addr>,
+) -> Result<Response, ContractError> {
+    match &admin {
+        Some(admin) => ADMIN.save(deps.storage, admin)?,
+        None => ADMIN.remove(deps.storage),
+    }
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "update_admin")
+        .add_attribute(
+            "admin",
+            admin.map_or("none".to_string(), |a| a.into_string()),
+        ))
+}
@@ -1,17 +1,25 @@
 #[cfg(test)]
 use crate::error::ContractError;
 
-use crate::contract::{execute, instantiate, sudo};
-use crate::msg::{ExecuteMsg, SudoMsg};
-use crate::query::{query_offers_by_peer, query_offers_by_sender};
-use crate::state::{offers, MAX_EXPIRY, MIN_EXPIRY};
+use crate::contract::{execute, instantiate, migrate, sudo};
+use crate::msg::{ExecuteMsg, OfferFilter, OfferFilterStatus, SudoMsg, TokenMsg};
+use crate::query::{
+    query_admin, query_blocked_senders, query_config, query_inbox_preferences, query_offers,
+    query_offers_by_collection, query_offers_by_peer, query_offers_by_sender, query_reputation,
+    query_stats, query_token_interest, query_user_stats,
+};
+use crate::state::{
+    add_offer_to_stats, offers, remove_offer_from_stats, MAX_BUNDLE_LIMIT, MAX_EXPIRY,
+    MAX_INCOMING_OFFERS_LIMIT, MAX_MEMO_LENGTH_LIMIT, MAX_OFFERS_LIMIT, MIN_BUNDLE_LIMIT,
+    MIN_EXPIRY, MIN_INCOMING_OFFERS_LIMIT, MIN_MEMO_LENGTH_LIMIT, MIN_OFFERS_LIMIT, SUDO_PARAMS,
+};
 use crate::{
     msg::InstantiateMsg,
-    state::{Offer, Token},
+    state::{Offer, Token, TradeSide},
     ExpiryRange,
 };
 
-use cosmwasm_std::{testing::*, Addr, DepsMut, StdError, Timestamp};
+use cosmwasm_std::{testing::*, Addr, Decimal, Deps, DepsMut, Empty, StdError, Timestamp};
 
 const CREATOR: &str = "creator";
 const COLLECTION_A: &str = "collection-a";
@@ -22,6 +30,7 @@ const TOKEN2_ID: u32 = 234;
 const SENDER: &str = "sender";
 // const SENDER2: &str = "sender";
 const PEER: &str = "peer";
+const ADMIN: &str = "admin";
 //---------------------------------------------------------
 // Unit tests without Cw721Queries
 //---------------------------------------------------------
@@ -128,6 +137,21 @@ fn reject_offer() {
     let res = execute(deps.as_mut(), mock_env(), mock_peer, exec_msg.clone());
     assert!(res.is_ok(), "Peer of the Offer cant reject");
 
+    // The rejected offer should no longer count towards the user stats
+    let stats = query_user_stats(deps.as_ref(), Addr::unchecked(SENDER)).unwrap();
+    assert_eq!(stats.stats.offers_sent, 0);
+    let stats = query_user_stats(deps.as_ref(), Addr::unchecked(PEER)).unwrap();
+    assert_eq!(stats.stats.offers_received, 0);
+    let stats = query_stats(deps.as_ref()).unwrap();
+    assert_eq!(stats.stats.offers_rejected, 1);
+
+    // The rejection ends up on the record of the sender only
+    let res = query_reputation(deps.as_ref(), Addr::unchecked(SENDER)).unwrap();
+    assert_eq!(res.history.rejections_received, 1);
+    assert_eq!(res.reputation, Some(Decimal::zero()));
+    let res = query_reputation(deps.as_ref(), Addr::unchecked(PEER)).unwrap();
+    assert_eq!(res.reputation, None);
+
     // test for non existing offer
     let remove_nonexisting_msg = ExecuteMsg::RejectOffer { id: 1 };
 
@@ -147,6 +171,269 @@ fn reject_offer() {
     )
 }
 
+#[test]
+fn duplicate_tokens() {
+    let mut deps = mock_dependencies();
+    instantiate_trade_contract(deps.as_mut());
+
+    let token = |collection: &str, token_id| TokenMsg {
+        collection: collection.to_string(),
+        token_id,
+    };
+    let create_msg = |offered_nfts, wanted_nfts| ExecuteMsg::CreateOffer {
+        offered_nfts,
+        wanted_nfts,
+        peer: PEER.to_string(),
+        expires_at: None,
+        memo: None,
+    };
+    let duplicate = ContractError::DuplicateToken {
+        collection: COLLECTION_A.to_string(),
+        token_id: TOKEN1_ID,
+    };
+
+    // twice on the offered side
+    let msg = create_msg(
+        vec![
+            token(COLLECTION_A, TOKEN1_ID),
+            token(COLLECTION_A, TOKEN1_ID),
+        ],
+        vec![token(COLLECTION_B, TOKEN2_ID)],
+    );
+    let err = execute(deps.as_mut(), mock_env(), mock_info(SENDER, &[]), msg).unwrap_err();
+    assert_eq!(err, duplicate);
+
+    // on both sides
+    let msg = create_msg(
+        vec![token(COLLECTION_A, TOKEN1_ID)],
+        vec![
+            token(COLLECTION_B, TOKEN2_ID),
+            token(COLLECTION_A, TOKEN1_ID),
+        ],
+    );
+    let err = execute(deps.as_mut(), mock_env(), mock_info(SENDER, &[]), msg).unwrap_err();
+    assert_eq!(err, duplicate);
+}
+
+#[test]
+fn inbox_preferences() {
+    let mut deps = mock_dependencies();
+    instantiate_trade_contract(deps.as_mut());
+
+    let create_msg = |offered: Vec<u32>| ExecuteMsg::CreateOffer {
+        offered_nfts: offered
+            .into_iter()
+            .map(|token_id| TokenMsg {
+                collection: COLLECTION_A.to_string(),
+                token_id,
+            })
+            .collect(),
+        wanted_nfts: vec![TokenMsg {
+            collection: COLLECTION_B.to_string(),
+            token_id: TOKEN2_ID,
+        }],
+        peer: PEER.to_string(),
+        expires_at: None,
+        memo: None,
+    };
+
+    // a sender that isn't on the allowlist can't send offers to the peer
+    let exec_msg = ExecuteMsg::SetInboxPreferences {
+        allowed_senders: Some(vec![CREATOR.to_string()]),
+        min_offered_nfts: 0,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(PEER, &[]), exec_msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SENDER, &[]),
+        create_msg(vec![TOKEN1_ID]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SenderNotAccepted {
+            sender: SENDER.to_string(),
+            peer: PEER.to_string()
+        }
+    );
+
+    // offers need to contain the minimum amount of NFTs
+    let exec_msg = ExecuteMsg::SetInboxPreferences {
+        allowed_senders: None,
+        min_offered_nfts: 2,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(PEER, &[]), exec_msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SENDER, &[]),
+        create_msg(vec![TOKEN1_ID]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MinOfferedNfts {
+            peer: PEER.to_string(),
+            min: 2
+        }
+    );
+
+    let prefs = query_inbox_preferences(deps.as_ref(), Addr::unchecked(PEER)).unwrap();
+    assert_eq!(prefs.preferences.min_offered_nfts, 2);
+
+    // the peer can't receive more offers than the incoming offer limit
+    let sudo_msg = SudoMsg::UpdateParams {
+        offer_expiry: None,
+        maintainer: None,
+        max_offers: None,
+        max_incoming_offers: Some(1),
+        bundle_limit: None,
+        max_memo_length: None,
+    };
+    sudo(deps.as_mut(), mock_env(), sudo_msg).unwrap();
+
+    let offered_nfts = vec![Token {
+        collection: Addr::unchecked(COLLECTION_A),
+        token_id: TOKEN1_ID,
+    }];
+    let wanted_nfts = vec![Token {
+        collection: Addr::unchecked(COLLECTION_B),
+        token_id: TOKEN2_ID,
+    }];
+    save_new_offer(deps.as_mut(), CREATOR, PEER, 0, offered_nfts, wanted_nfts);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SENDER, &[]),
+        create_msg(vec![TOKEN1_ID, TOKEN2_ID]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MaxIncomingOffers {
+            addr: PEER.to_string(),
+            max_offers: 1
+        }
+    );
+}
+
+#[test]
+fn block_sender() {
+    let mut deps = mock_dependencies();
+    instantiate_trade_contract(deps.as_mut());
+
+    let collection = Addr::unchecked(COLLECTION_A);
+    for id in 0..2 {
+        let offered_nfts = vec![Token {
+            collection: collection.clone(),
+            token_id: TOKEN1_ID + id as u32,
+        }];
+        let wanted_nfts = vec![Token {
+            collection: collection.clone(),
+            token_id: TOKEN2_ID,
+        }];
+        save_new_offer(deps.as_mut(), SENDER, PEER, id, offered_nfts, wanted_nfts);
+    }
+    // offers between other addresses aren't affected by the block
+    for &(id, sender, peer) in [(2, SENDER, CREATOR), (3, CREATOR, PEER)].iter() {
+        let offered_nfts = vec![Token {
+            collection: collection.clone(),
+            token_id: TOKEN1_ID,
+        }];
+        let wanted_nfts = vec![Token {
+            collection: collection.clone(),
+            token_id: TOKEN2_ID,
+        }];
+        save_new_offer(deps.as_mut(), sender, peer, id, offered_nfts, wanted_nfts);
+    }
+
+    // offers can only be rejected in bulk once the sender is blocked
+    let reject_msg = ExecuteMsg::RejectBlockedOffers {
+        sender: SENDER.to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(PEER, &[]),
+        reject_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SenderNotBlocked {
+            sender: SENDER.to_string()
+        }
+    );
+
+    let block_msg = ExecuteMsg::BlockSender {
+        sender: PEER.to_string(),
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(PEER, &[]), block_msg).unwrap_err();
+    assert_eq!(err, ContractError::CannotBlockSelf {});
+
+    let block_msg = ExecuteMsg::BlockSender {
+        sender: SENDER.to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(PEER, &[]), block_msg).unwrap();
+
+    let blocked = query_blocked_senders(deps.as_ref(), Addr::unchecked(PEER)).unwrap();
+    assert_eq!(blocked.senders, vec![Addr::unchecked(SENDER)]);
+
+    // a blocked sender can't send offers to the peer
+    let create_msg = ExecuteMsg::CreateOffer {
+        offered_nfts: vec![TokenMsg {
+            collection: COLLECTION_A.to_string(),
+            token_id: TOKEN1_ID,
+        }],
+        wanted_nfts: vec![TokenMsg {
+            collection: COLLECTION_A.to_string(),
+            token_id: TOKEN2_ID,
+        }],
+        peer: PEER.to_string(),
+        expires_at: None,
+        memo: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SENDER, &[]),
+        create_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SenderNotAccepted {
+            sender: SENDER.to_string(),
+            peer: PEER.to_string()
+        }
+    );
+
+    // all existing offers from the blocked sender are rejected at once
+    let res = execute(deps.as_mut(), mock_env(), mock_info(PEER, &[]), reject_msg).unwrap();
+    assert_eq!(
+        res.attributes.last().unwrap().value,
+        "2",
+        "Not all offers from the blocked sender are rejected"
+    );
+    let res = query_offers_by_peer(deps.as_ref(), Addr::unchecked(PEER)).unwrap();
+    assert_eq!(res.offers.len(), 1);
+    assert_eq!(res.offers[0].sender, Addr::unchecked(CREATOR));
+    let stats = query_user_stats(deps.as_ref(), Addr::unchecked(SENDER)).unwrap();
+    assert_eq!(stats.stats.offers_sent, 1);
+
+    let unblock_msg = ExecuteMsg::UnblockSender {
+        sender: SENDER.to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(PEER, &[]), unblock_msg).unwrap();
+
+    let blocked = query_blocked_senders(deps.as_ref(), Addr::unchecked(PEER)).unwrap();
+    assert!(blocked.senders.is_empty());
+}
+
 #[test]
 fn test_sudo_update() {
     let mut deps = mock_dependencies();
@@ -161,14 +448,160 @@ fn test_sudo_update() {
         offer_expiry: Some(invalid_exp_range),
         maintainer: Some(CREATOR.to_string()),
         max_offers: Some(10),
+        max_incoming_offers: Some(10),
         bundle_limit: Some(10),
+        max_memo_length: Some(10),
     };
 
-    let err = sudo(deps.as_mut(), env, sudo_msg).unwrap_err();
+    let err = sudo(deps.as_mut(), env.clone(), sudo_msg).unwrap_err();
     assert_eq!(
         err,
         ContractError::ExpiryRange(crate::ExpiryRangeError::InvalidExpirationRange {})
     );
+
+    // limits have to stay within their bounds
+    let sudo_msg = SudoMsg::UpdateParams {
+        offer_expiry: None,
+        maintainer: None,
+        max_offers: Some(0),
+        max_incoming_offers: None,
+        bundle_limit: None,
+        max_memo_length: None,
+    };
+    let err = sudo(deps.as_mut(), env.clone(), sudo_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidMaxOffers {
+            max_offers: 0,
+            min: MIN_OFFERS_LIMIT,
+            max: MAX_OFFERS_LIMIT
+        }
+    );
+    let sudo_msg = SudoMsg::UpdateParams {
+        offer_expiry: None,
+        maintainer: None,
+        max_offers: None,
+        max_incoming_offers: None,
+        bundle_limit: Some(MAX_BUNDLE_LIMIT + 1),
+        max_memo_length: None,
+    };
+    let err = sudo(deps.as_mut(), env.clone(), sudo_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBundleLimit {
+            bundle_limit: MAX_BUNDLE_LIMIT + 1,
+            min: MIN_BUNDLE_LIMIT,
+            max: MAX_BUNDLE_LIMIT
+        }
+    );
+    let sudo_msg = SudoMsg::UpdateParams {
+        offer_expiry: None,
+        maintainer: None,
+        max_offers: None,
+        max_incoming_offers: Some(0),
+        bundle_limit: None,
+        max_memo_length: None,
+    };
+    let err = sudo(deps.as_mut(), env.clone(), sudo_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidMaxIncomingOffers {
+            max_incoming_offers: 0,
+            min: MIN_INCOMING_OFFERS_LIMIT,
+            max: MAX_INCOMING_OFFERS_LIMIT
+        }
+    );
+    let sudo_msg = SudoMsg::UpdateParams {
+        offer_expiry: None,
+        maintainer: None,
+        max_offers: None,
+        max_incoming_offers: None,
+        bundle_limit: None,
+        max_memo_length: Some(MAX_MEMO_LENGTH_LIMIT + 1),
+    };
+    let err = sudo(deps.as_mut(), env, sudo_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidMaxMemoLength {
+            max_memo_length: MAX_MEMO_LENGTH_LIMIT + 1,
+            min: MIN_MEMO_LENGTH_LIMIT,
+            max: MAX_MEMO_LENGTH_LIMIT
+        }
+    );
+
+    let res = query_config(deps.as_ref()).unwrap();
+    assert_eq!((res.max_offers, res.bundle_limit), (16, 5));
+    assert_eq!(res.max_offers_bounds.max, MAX_OFFERS_LIMIT);
+    assert_eq!(
+        res.max_incoming_offers_bounds.max,
+        MAX_INCOMING_OFFERS_LIMIT
+    );
+    assert_eq!(res.max_memo_length_bounds.max, MAX_MEMO_LENGTH_LIMIT);
+}
+
+#[test]
+fn test_admin() {
+    let mut deps = mock_dependencies();
+    instantiate_trade_contract(deps.as_mut());
+
+    // only the admin can update the limits
+    let update_msg = ExecuteMsg::UpdateAdminParams {
+        max_offers: Some(4),
+        max_incoming_offers: None,
+        bundle_limit: Some(2),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SENDER, &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotAdmin {});
+    execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), update_msg).unwrap();
+    let params = SUDO_PARAMS.load(&deps.storage).unwrap();
+    assert_eq!((params.max_offers, params.bundle_limit), (4, 2));
+    assert_eq!(params.max_incoming_offers, 16);
+
+    // rotating the admin takes a proposal and an acceptance
+    let propose_msg = ExecuteMsg::ProposeAdmin {
+        admin: SENDER.to_string(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        propose_msg,
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(PEER, &[]),
+        ExecuteMsg::AcceptAdmin {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotPendingAdmin {});
+    let res = query_admin(deps.as_ref()).unwrap();
+    assert_eq!(res.admin, Some(Addr::unchecked(ADMIN)));
+    assert_eq!(res.pending_admin, Some(Addr::unchecked(SENDER)));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SENDER, &[]),
+        ExecuteMsg::AcceptAdmin {},
+    )
+    .unwrap();
+    let res = query_admin(deps.as_ref()).unwrap();
+    assert_eq!(res.admin, Some(Addr::unchecked(SENDER)));
+    assert_eq!(res.pending_admin, None);
+
+    // governance can always remove the admin
+    let sudo_msg = SudoMsg::UpdateAdmin { admin: None };
+    sudo(deps.as_mut(), mock_env(), sudo_msg).unwrap();
+    let res = query_admin(deps.as_ref()).unwrap();
+    assert_eq!(res.admin, None);
 }
 
 #[test]
@@ -194,6 +627,72 @@ fn test_query_indexes() {
 
     assert_eq!(res_sender.offers.len(), 1, "indexing by sender inst right");
     assert_eq!(res.offers.len(), 1, "indexing by peer isnt right");
+
+    let sender_stats = query_user_stats(deps.as_ref(), Addr::unchecked(SENDER)).unwrap();
+    let peer_stats = query_user_stats(deps.as_ref(), Addr::unchecked(PEER)).unwrap();
+
+    assert_eq!(
+        sender_stats.stats.offers_sent, 1,
+        "sender stats arent right"
+    );
+    assert_eq!(
+        peer_stats.stats.offers_received, 1,
+        "peer stats arent right"
+    );
+
+    // an offer is indexed once per collection and side
+    let by_collection = |deps: Deps, collection, side| {
+        query_offers_by_collection(deps, Addr::unchecked(collection), side, None, None)
+            .unwrap()
+            .offers
+            .len()
+    };
+    save_new_offer(
+        deps.as_mut(),
+        SENDER,
+        PEER,
+        1,
+        vec![Token {
+            collection: Addr::unchecked(COLLECTION_B),
+            token_id: TOKEN1_ID,
+        }],
+        vec![
+            Token {
+                collection: Addr::unchecked(COLLECTION_A),
+                token_id: TOKEN1_ID,
+            },
+            Token {
+                collection: Addr::unchecked(COLLECTION_A),
+                token_id: TOKEN2_ID,
+            },
+        ],
+    );
+    assert_eq!(
+        by_collection(deps.as_ref(), COLLECTION_A, TradeSide::Offered),
+        1
+    );
+    assert_eq!(
+        by_collection(deps.as_ref(), COLLECTION_A, TradeSide::Wanted),
+        2
+    );
+    assert_eq!(
+        by_collection(deps.as_ref(), COLLECTION_B, TradeSide::Offered),
+        1
+    );
+    assert_eq!(
+        by_collection(deps.as_ref(), COLLECTION_B, TradeSide::Wanted),
+        0
+    );
+
+    offers().remove(deps.as_mut().storage, 1).unwrap();
+    assert_eq!(
+        by_collection(deps.as_ref(), COLLECTION_A, TradeSide::Wanted),
+        1
+    );
+    assert_eq!(
+        by_collection(deps.as_ref(), COLLECTION_B, TradeSide::Offered),
+        0
+    );
 }
 
 //---------------------------------------------------------
@@ -213,20 +712,278 @@ fn save_new_offer(
     let peer = Addr::unchecked(peer);
 
     let offer = Offer {
-        id: id,
-        offered_nfts: offered_nfts,
-        wanted_nfts: wanted_nfts,
-        sender: sender,
-        peer: peer,
+        id,
+        offered_nfts,
+        wanted_nfts,
+        sender,
+        peer,
         expires_at: Timestamp::from_seconds(mock_env().block.time.plus_seconds(100_000).seconds()),
         created_at: mock_env().block.time,
+        escrowed: false,
+        memo: None,
     };
     // let res = offers().save(deps.storage, offer.id, &offer);
     let res = offers().save(deps.storage, offer.id, &offer);
     assert!(res.is_ok(), "Failed to save offer to storage");
+    let res = add_offer_to_stats(deps.storage, &offer);
+    assert!(res.is_ok(), "Failed to count offer in user stats");
+}
+
+#[test]
+fn test_query_offers_filter() {
+    let mut deps = mock_dependencies();
+    instantiate_trade_contract(deps.as_mut());
+
+    let token = |collection: &str, token_id| Token {
+        collection: Addr::unchecked(collection),
+        token_id,
+    };
+    let offers = [
+        (SENDER, PEER, COLLECTION_A, COLLECTION_B),
+        (SENDER, CREATOR, COLLECTION_B, COLLECTION_B),
+        (CREATOR, PEER, COLLECTION_A, COLLECTION_A),
+    ];
+    for (id, &(sender, peer, offered, wanted)) in offers.iter().enumerate() {
+        save_new_offer(
+            deps.as_mut(),
+            sender,
+            peer,
+            id as u64,
+            vec![token(offered, TOKEN1_ID + id as u32)],
+            vec![token(wanted, TOKEN2_ID + id as u32)],
+        );
+    }
+
+    let ids = |filter: OfferFilter, env| {
+        query_offers(deps.as_ref(), env, filter, None, None)
+            .unwrap()
+            .offers
+            .iter()
+            .map(|offer| offer.id)
+            .collect::<Vec<_>>()
+    };
+    let filter = OfferFilter {
+        sender: Some(SENDER.to_string()),
+        ..OfferFilter::default()
+    };
+    assert_eq!(ids(filter, mock_env()), vec![0, 1]);
+    let filter = OfferFilter {
+        peer: Some(PEER.to_string()),
+        collection: Some(COLLECTION_A.to_string()),
+        ..OfferFilter::default()
+    };
+    assert_eq!(ids(filter, mock_env()), vec![0, 2]);
+    let filter = OfferFilter {
+        collection: Some(COLLECTION_B.to_string()),
+        ..OfferFilter::default()
+    };
+    assert_eq!(ids(filter, mock_env()), vec![0, 1]);
+
+    // all offers expire at the same time
+    let filter = OfferFilter {
+        status: Some(OfferFilterStatus::Expired),
+        ..OfferFilter::default()
+    };
+    assert!(ids(filter.clone(), mock_env()).is_empty());
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100_000);
+    assert_eq!(ids(filter, env), vec![0, 1, 2]);
+    let filter = OfferFilter {
+        expires_before: Some(mock_env().block.time.plus_seconds(100_001)),
+        ..OfferFilter::default()
+    };
+    assert_eq!(ids(filter, mock_env()), vec![0, 1, 2]);
+
+    // paginate through the offers of the sender
+    let filter = OfferFilter {
+        sender: Some(SENDER.to_string()),
+        ..OfferFilter::default()
+    };
+    let res = query_offers(deps.as_ref(), mock_env(), filter.clone(), None, Some(1)).unwrap();
+    assert_eq!(res.offers[0].id, 0);
+    assert_eq!(res.last_scanned, Some(0));
+    let res = query_offers(deps.as_ref(), mock_env(), filter.clone(), Some(0), Some(1)).unwrap();
+    assert_eq!(res.offers[0].id, 1);
+    let res = query_offers(deps.as_ref(), mock_env(), filter, Some(1), Some(1)).unwrap();
+    assert!(res.offers.is_empty());
+    assert_eq!(res.last_scanned, None);
+
+    // collection filters go through the collection index, offers with the
+    // collection on both sides are returned once
+    let filter = OfferFilter {
+        collection: Some(COLLECTION_A.to_string()),
+        ..OfferFilter::default()
+    };
+    let res = query_offers(deps.as_ref(), mock_env(), filter.clone(), None, Some(1)).unwrap();
+    assert_eq!(res.offers[0].id, 0);
+    assert_eq!(res.last_scanned, Some(0));
+    let res = query_offers(deps.as_ref(), mock_env(), filter, Some(0), Some(1)).unwrap();
+    assert_eq!(res.offers[0].id, 2);
+    assert_eq!(res.last_scanned, Some(2));
+    let filter = OfferFilter {
+        collection: Some(COLLECTION_B.to_string()),
+        ..OfferFilter::default()
+    };
+    let res = query_offers(deps.as_ref(), mock_env(), filter, Some(0), None).unwrap();
+    assert_eq!(res.offers.len(), 1);
+    assert_eq!(res.offers[0].id, 1);
+}
+
+#[test]
+fn test_token_interest() {
+    let mut deps = mock_dependencies();
+    instantiate_trade_contract(deps.as_mut());
+
+    let token = |collection: &str, token_id| Token {
+        collection: Addr::unchecked(collection),
+        token_id,
+    };
+    let wanted = token(COLLECTION_A, TOKEN1_ID);
+    save_new_offer(
+        deps.as_mut(),
+        SENDER,
+        PEER,
+        1,
+        vec![token(COLLECTION_B, TOKEN1_ID)],
+        vec![wanted.clone()],
+    );
+    save_new_offer(
+        deps.as_mut(),
+        CREATOR,
+        PEER,
+        2,
+        vec![
+            token(COLLECTION_A, TOKEN2_ID),
+            token(COLLECTION_B, TOKEN2_ID),
+        ],
+        vec![wanted.clone(), token(COLLECTION_B, TOKEN2_ID)],
+    );
+
+    let interest = |deps: Deps, env, token: &Token| {
+        query_token_interest(deps, env, token.collection.clone(), token.token_id).unwrap()
+    };
+    let res = interest(deps.as_ref(), mock_env(), &wanted);
+    assert_eq!(res.open_offers, 2);
+    assert_eq!(
+        res.offered_collections,
+        vec![Addr::unchecked(COLLECTION_A), Addr::unchecked(COLLECTION_B)]
+    );
+    assert_eq!(res.latest_offers, vec![mock_env().block.time; 2]);
+    assert!(res.complete);
+    assert_eq!(
+        interest(deps.as_ref(), mock_env(), &token(COLLECTION_B, TOKEN2_ID)).open_offers,
+        1
+    );
+    // offered NFTs don't count as interest
+    assert_eq!(
+        interest(deps.as_ref(), mock_env(), &token(COLLECTION_B, TOKEN1_ID)).open_offers,
+        0
+    );
+
+    // expired offers are left out
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100_000);
+    let res = interest(deps.as_ref(), env, &wanted);
+    assert_eq!(res.open_offers, 0);
+    assert!(res.offered_collections.is_empty());
+
+    // removed offers are dropped from the index
+    offers().remove(deps.as_mut().storage, 2).unwrap();
+    let res = interest(deps.as_ref(), mock_env(), &wanted);
+    assert_eq!(res.open_offers, 1);
+    assert_eq!(res.offered_collections, vec![Addr::unchecked(COLLECTION_B)]);
 }
 
 // setup contract helper
+#[test]
+fn test_migrate() {
+    let mut deps = mock_dependencies();
+    instantiate_trade_contract(deps.as_mut());
+
+    // params stored before max_incoming_offers and max_memo_length existed can still be loaded
+    deps.as_mut().storage.set(
+        b"sudo-params",
+        br#"{"offer_expiry":{"min":86400,"max":604800},"maintainer":"creator","max_offers":16,"bundle_limit":5}"#,
+    );
+    let params = SUDO_PARAMS.load(&deps.storage).unwrap();
+    assert_eq!(params.max_incoming_offers, MAX_INCOMING_OFFERS_LIMIT);
+    assert_eq!(params.max_memo_length, 0);
+
+    // offers stored by an older version aren't counted in the user stats
+    for id in 1..=2 {
+        let offer = Offer {
+            id,
+            offered_nfts: vec![Token {
+                collection: Addr::unchecked(COLLECTION_A),
+                token_id: TOKEN1_ID + id as u32,
+            }],
+            wanted_nfts: vec![Token {
+                collection: Addr::unchecked(COLLECTION_B),
+                token_id: TOKEN2_ID,
+            }],
+            sender: Addr::unchecked(SENDER),
+            peer: Addr::unchecked(PEER),
+            created_at: mock_env().block.time,
+            expires_at: mock_env().block.time.plus_seconds(100_000),
+            escrowed: false,
+            memo: None,
+        };
+        offers().save(deps.as_mut().storage, id, &offer).unwrap();
+
+        // uncounting it would drive the counts below zero
+        let res = remove_offer_from_stats(deps.as_mut().storage, &offer);
+        assert!(res.is_err());
+    }
+
+    // the deployed version can't be migrated onto itself
+    let res = migrate(deps.as_mut(), mock_env(), Empty {});
+    assert!(res.is_err());
+
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:pegasus", "1.0.0").unwrap();
+    migrate(deps.as_mut(), mock_env(), Empty {}).unwrap();
+
+    // the offers have to be rebuilt before anything else
+    let remove_msg = ExecuteMsg::RemoveOffer { id: 1 };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SENDER, &[]),
+        remove_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RebuildPending {});
+
+    // in as many batches as needed, by anyone
+    let rebuild_msg = ExecuteMsg::RebuildOffers { limit: Some(1) };
+    for complete in ["false", "true"] {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(PEER, &[]),
+            rebuild_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes.last().unwrap().value, complete);
+    }
+    let err = execute(deps.as_mut(), mock_env(), mock_info(PEER, &[]), rebuild_msg).unwrap_err();
+    assert_eq!(err, ContractError::NoRebuildPending {});
+
+    let stats = query_user_stats(deps.as_ref(), Addr::unchecked(SENDER)).unwrap();
+    assert_eq!(stats.stats.offers_sent, 2);
+    let stats = query_user_stats(deps.as_ref(), Addr::unchecked(PEER)).unwrap();
+    assert_eq!(stats.stats.offers_received, 2);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SENDER, &[]),
+        remove_msg,
+    );
+    assert!(res.is_ok());
+    let stats = query_user_stats(deps.as_ref(), Addr::unchecked(SENDER)).unwrap();
+    assert_eq!(stats.stats.offers_sent, 1);
+}
+
 fn instantiate_trade_contract(deps: DepsMut) {
     let msg = InstantiateMsg {
         offer_expiry: ExpiryRange {
@@ -235,7 +992,10 @@ fn instantiate_trade_contract(deps: DepsMut) {
         },
         maintainer: CREATOR.to_owned(),
         max_offers: 16,
+        max_incoming_offers: 16,
         bundle_limit: 5,
+        max_memo_length: 64,
+        admin: Some(ADMIN.to_string()),
     };
     let info = mock_info(CREATOR, &[]);
     let res = instantiate(deps, mock_env(), info, msg).unwrap();
@@ -6,7 +6,7 @@
 
 import { CosmWasmClient, SigningCosmWasmClient, ExecuteResult } from "@cosmjs/cosmwasm-stargate";
 import { Coin, StdFee } from "@cosmjs/amino";
-import { ExecuteMsg, Timestamp, Uint64, TokenMsg, InstantiateMsg, ExpiryRange, Addr, OfferResponse, Offer, Token, OffersResponse, ParamsResponse, SudoParams, QueryMsg } from "./Pegasus.types";
+import { Addr, AdminResponse, BlockedSendersResponse, CollectionParamsResponse, CollectionParams, ExpiryRange, CollectionStatsResponse, CollectionStats, ConfigResponse, LimitBounds, DepositsResponse, Token, ExecuteMsg, Timestamp, Uint64, Binary, TokenMsg, Cw721ReceiveMsg, FilteredOffersResponse, Offer, HooksResponse, InboxPreferencesResponse, InboxPreferences, InstantiateMsg, OfferHookExecuteMsg, OfferHookMsg, OfferResponse, OfferStatusResponse, OfferStatus, TokenStatus, OffersHealthResponse, OffersResponse, ParamsResponse, SudoParams, QueryMsg, TradeSide, OfferFilterStatus, OfferFilter, ReceiveNftMsg, Decimal, ReputationResponse, TradeHistory, AcceptIssue, SimulateAcceptResponse, StatsResponse, TradeStats, TokenInterestResponse, UserStatsResponse, UserStats } from "./Pegasus.types";
 export interface PegasusReadOnlyInterface {
   contractAddress: string;
   offer: ({
@@ -24,6 +24,106 @@ export interface PegasusReadOnlyInterface {
   }: {
     peer: string;
   }) => Promise<OffersByPeerResponse>;
+  userStats: ({
+    address
+  }: {
+    address: string;
+  }) => Promise<UserStatsResponse>;
+  stats: () => Promise<StatsResponse>;
+  collectionStats: ({
+    collection
+  }: {
+    collection: string;
+  }) => Promise<CollectionStatsResponse>;
+  reputation: ({
+    address
+  }: {
+    address: string;
+  }) => Promise<ReputationResponse>;
+  tokenInterest: ({
+    collection,
+    tokenId
+  }: {
+    collection: string;
+    tokenId: number;
+  }) => Promise<TokenInterestResponse>;
+  fillableOffers: ({
+    collection,
+    limit,
+    owner,
+    startAfter
+  }: {
+    collection: string;
+    limit?: number;
+    owner: string;
+    startAfter?: number;
+  }) => Promise<FillableOffersResponse>;
+  inboxPreferences: ({
+    address
+  }: {
+    address: string;
+  }) => Promise<InboxPreferencesResponse>;
+  deposits: ({
+    limit,
+    owner,
+    startAfter
+  }: {
+    limit?: number;
+    owner: string;
+    startAfter?: TokenMsg;
+  }) => Promise<DepositsResponse>;
+  blockedSenders: ({
+    owner
+  }: {
+    owner: string;
+  }) => Promise<BlockedSendersResponse>;
+  offerHooks: () => Promise<OfferHooksResponse>;
+  admin: () => Promise<AdminResponse>;
+  simulateAccept: ({
+    accepter,
+    id
+  }: {
+    accepter: string;
+    id: number;
+  }) => Promise<SimulateAcceptResponse>;
+  offerStatus: ({
+    id
+  }: {
+    id: number;
+  }) => Promise<OfferStatusResponse>;
+  offersHealth: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: number;
+  }) => Promise<OffersHealthResponse>;
+  offersByCollection: ({
+    collection,
+    limit,
+    side,
+    startAfter
+  }: {
+    collection: string;
+    limit?: number;
+    side: TradeSide;
+    startAfter?: number;
+  }) => Promise<OffersByCollectionResponse>;
+  offers: ({
+    filter,
+    limit,
+    startAfter
+  }: {
+    filter: OfferFilter;
+    limit?: number;
+    startAfter?: number;
+  }) => Promise<OffersResponse>;
+  config: () => Promise<ConfigResponse>;
+  collectionParams: ({
+    collection
+  }: {
+    collection: string;
+  }) => Promise<CollectionParamsResponse>;
   params: () => Promise<ParamsResponse>;
 }
 export class PegasusQueryClient implements PegasusReadOnlyInterface {
@@ -35,6 +135,24 @@ export class PegasusQueryClient implements PegasusReadOnlyInterface {
     this.offer = this.offer.bind(this);
     this.offersBySender = this.offersBySender.bind(this);
     this.offersByPeer = this.offersByPeer.bind(this);
+    this.userStats = this.userStats.bind(this);
+    this.stats = this.stats.bind(this);
+    this.collectionStats = this.collectionStats.bind(this);
+    this.reputation = this.reputation.bind(this);
+    this.tokenInterest = this.tokenInterest.bind(this);
+    this.fillableOffers = this.fillableOffers.bind(this);
+    this.inboxPreferences = this.inboxPreferences.bind(this);
+    this.deposits = this.deposits.bind(this);
+    this.blockedSenders = this.blockedSenders.bind(this);
+    this.offerHooks = this.offerHooks.bind(this);
+    this.admin = this.admin.bind(this);
+    this.simulateAccept = this.simulateAccept.bind(this);
+    this.offerStatus = this.offerStatus.bind(this);
+    this.offersHealth = this.offersHealth.bind(this);
+    this.offersByCollection = this.offersByCollection.bind(this);
+    this.offers = this.offers.bind(this);
+    this.config = this.config.bind(this);
+    this.collectionParams = this.collectionParams.bind(this);
     this.params = this.params.bind(this);
   }
   offer = async ({
@@ -70,6 +188,219 @@ export class PegasusQueryClient implements PegasusReadOnlyInterface {
       }
     });
   };
+  userStats = async ({
+    address
+  }: {
+    address: string;
+  }): Promise<UserStatsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      user_stats: {
+        address
+      }
+    });
+  };
+  stats = async (): Promise<StatsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      stats: {}
+    });
+  };
+  collectionStats = async ({
+    collection
+  }: {
+    collection: string;
+  }): Promise<CollectionStatsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      collection_stats: {
+        collection
+      }
+    });
+  };
+  reputation = async ({
+    address
+  }: {
+    address: string;
+  }): Promise<ReputationResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      reputation: {
+        address
+      }
+    });
+  };
+  tokenInterest = async ({
+    collection,
+    tokenId
+  }: {
+    collection: string;
+    tokenId: number;
+  }): Promise<TokenInterestResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      token_interest: {
+        collection,
+        token_id: tokenId
+      }
+    });
+  };
+  fillableOffers = async ({
+    collection,
+    limit,
+    owner,
+    startAfter
+  }: {
+    collection: string;
+    limit?: number;
+    owner: string;
+    startAfter?: number;
+  }): Promise<FillableOffersResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      fillable_offers: {
+        collection,
+        limit,
+        owner,
+        start_after: startAfter
+      }
+    });
+  };
+  inboxPreferences = async ({
+    address
+  }: {
+    address: string;
+  }): Promise<InboxPreferencesResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      inbox_preferences: {
+        address
+      }
+    });
+  };
+  deposits = async ({
+    limit,
+    owner,
+    startAfter
+  }: {
+    limit?: number;
+    owner: string;
+    startAfter?: TokenMsg;
+  }): Promise<DepositsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      deposits: {
+        limit,
+        owner,
+        start_after: startAfter
+      }
+    });
+  };
+  blockedSenders = async ({
+    owner
+  }: {
+    owner: string;
+  }): Promise<BlockedSendersResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      blocked_senders: {
+        owner
+      }
+    });
+  };
+  offerHooks = async (): Promise<OfferHooksResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      offer_hooks: {}
+    });
+  };
+  admin = async (): Promise<AdminResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      admin: {}
+    });
+  };
+  simulateAccept = async ({
+    accepter,
+    id
+  }: {
+    accepter: string;
+    id: number;
+  }): Promise<SimulateAcceptResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      simulate_accept: {
+        accepter,
+        id
+      }
+    });
+  };
+  offerStatus = async ({
+    id
+  }: {
+    id: number;
+  }): Promise<OfferStatusResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      offer_status: {
+        id
+      }
+    });
+  };
+  offersHealth = async ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: number;
+  }): Promise<OffersHealthResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      offers_health: {
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  offersByCollection = async ({
+    collection,
+    limit,
+    side,
+    startAfter
+  }: {
+    collection: string;
+    limit?: number;
+    side: TradeSide;
+    startAfter?: number;
+  }): Promise<OffersByCollectionResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      offers_by_collection: {
+        collection,
+        limit,
+        side,
+        start_after: startAfter
+      }
+    });
+  };
+  offers = async ({
+    filter,
+    limit,
+    startAfter
+  }: {
+    filter: OfferFilter;
+    limit?: number;
+    startAfter?: number;
+  }): Promise<OffersResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      offers: {
+        filter,
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  config = async (): Promise<ConfigResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      config: {}
+    });
+  };
+  collectionParams = async ({
+    collection
+  }: {
+    collection: string;
+  }): Promise<CollectionParamsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      collection_params: {
+        collection
+      }
+    });
+  };
   params = async (): Promise<ParamsResponse> => {
     return this.client.queryContractSmart(this.contractAddress, {
       params: {}
@@ -81,15 +412,17 @@ export interface PegasusInterface extends PegasusReadOnlyInterface {
   sender: string;
   createOffer: ({
     expiresAt,
+    memo,
     offeredNfts,
     peer,
     wantedNfts
   }: {
     expiresAt?: Timestamp;
+    memo?: string;
     offeredNfts: TokenMsg[];
     peer: string;
     wantedNfts: TokenMsg[];
-  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  }, fee?: number | StdFee | "auto", memo_?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
   removeOffer: ({
     id
   }: {
@@ -105,11 +438,80 @@ export interface PegasusInterface extends PegasusReadOnlyInterface {
   }: {
     id: number;
   }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  receiveNft: ({
+    msg,
+    sender,
+    tokenId
+  }: {
+    msg: Binary;
+    sender: string;
+    tokenId: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  createEscrowedOffer: ({
+    expiresAt,
+    memo,
+    offeredNfts,
+    peer,
+    wantedNfts
+  }: {
+    expiresAt?: Timestamp;
+    memo?: string;
+    offeredNfts: TokenMsg[];
+    peer: string;
+    wantedNfts: TokenMsg[];
+  }, fee?: number | StdFee | "auto", memo_?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  withdrawDeposits: ({
+    nfts
+  }: {
+    nfts: TokenMsg[];
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
   removeStaleOffer: ({
     id
   }: {
     id: number;
   }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  setInboxPreferences: ({
+    allowedSenders,
+    minOfferedNfts
+  }: {
+    allowedSenders?: string[];
+    minOfferedNfts: number;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  blockSender: ({
+    sender
+  }: {
+    sender: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  unblockSender: ({
+    sender
+  }: {
+    sender: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  rejectBlockedOffers: ({
+    sender
+  }: {
+    sender: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  updateAdminParams: ({
+    bundleLimit,
+    maxIncomingOffers,
+    maxOffers
+  }: {
+    bundleLimit?: number;
+    maxIncomingOffers?: number;
+    maxOffers?: number;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  proposeAdmin: ({
+    admin
+  }: {
+    admin: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  acceptAdmin: (fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  rebuildOffers: ({
+    limit
+  }: {
+    limit?: number;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
 }
 export class PegasusClient extends PegasusQueryClient implements PegasusInterface {
   client: SigningCosmWasmClient;
@@ -124,27 +526,41 @@ export class PegasusClient extends PegasusQueryClient implements PegasusInterfac
     this.removeOffer = this.removeOffer.bind(this);
     this.acceptOffer = this.acceptOffer.bind(this);
     this.rejectOffer = this.rejectOffer.bind(this);
+    this.receiveNft = this.receiveNft.bind(this);
+    this.createEscrowedOffer = this.createEscrowedOffer.bind(this);
+    this.withdrawDeposits = this.withdrawDeposits.bind(this);
     this.removeStaleOffer = this.removeStaleOffer.bind(this);
+    this.setInboxPreferences = this.setInboxPreferences.bind(this);
+    this.blockSender = this.blockSender.bind(this);
+    this.unblockSender = this.unblockSender.bind(this);
+    this.rejectBlockedOffers = this.rejectBlockedOffers.bind(this);
+    this.updateAdminParams = this.updateAdminParams.bind(this);
+    this.proposeAdmin = this.proposeAdmin.bind(this);
+    this.acceptAdmin = this.acceptAdmin.bind(this);
+    this.rebuildOffers = this.rebuildOffers.bind(this);
   }
   createOffer = async ({
     expiresAt,
+    memo,
     offeredNfts,
     peer,
     wantedNfts
   }: {
     expiresAt?: Timestamp;
+    memo?: string;
     offeredNfts: TokenMsg[];
     peer: string;
     wantedNfts: TokenMsg[];
-  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+  }, fee: number | StdFee | "auto" = "auto", memo_?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
     return await this.client.execute(this.sender, this.contractAddress, {
       create_offer: {
         expires_at: expiresAt,
+        memo,
         offered_nfts: offeredNfts,
         peer,
         wanted_nfts: wantedNfts
       }
-    }, fee, memo, _funds);
+    }, fee, memo_, _funds);
   };
   removeOffer = async ({
     id
@@ -179,6 +595,57 @@ export class PegasusClient extends PegasusQueryClient implements PegasusInterfac
       }
     }, fee, memo, _funds);
   };
+  receiveNft = async ({
+    msg,
+    sender,
+    tokenId
+  }: {
+    msg: Binary;
+    sender: string;
+    tokenId: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      receive_nft: {
+        msg,
+        sender,
+        token_id: tokenId
+      }
+    }, fee, memo, _funds);
+  };
+  createEscrowedOffer = async ({
+    expiresAt,
+    memo,
+    offeredNfts,
+    peer,
+    wantedNfts
+  }: {
+    expiresAt?: Timestamp;
+    memo?: string;
+    offeredNfts: TokenMsg[];
+    peer: string;
+    wantedNfts: TokenMsg[];
+  }, fee: number | StdFee | "auto" = "auto", memo_?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      create_escrowed_offer: {
+        expires_at: expiresAt,
+        memo,
+        offered_nfts: offeredNfts,
+        peer,
+        wanted_nfts: wantedNfts
+      }
+    }, fee, memo_, _funds);
+  };
+  withdrawDeposits = async ({
+    nfts
+  }: {
+    nfts: TokenMsg[];
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      withdraw_deposits: {
+        nfts
+      }
+    }, fee, memo, _funds);
+  };
   removeStaleOffer = async ({
     id
   }: {
@@ -190,4 +657,95 @@ export class PegasusClient extends PegasusQueryClient implements PegasusInterfac
       }
     }, fee, memo, _funds);
   };
+  setInboxPreferences = async ({
+    allowedSenders,
+    minOfferedNfts
+  }: {
+    allowedSenders?: string[];
+    minOfferedNfts: number;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      set_inbox_preferences: {
+        allowed_senders: allowedSenders,
+        min_offered_nfts: minOfferedNfts
+      }
+    }, fee, memo, _funds);
+  };
+  blockSender = async ({
+    sender
+  }: {
+    sender: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      block_sender: {
+        sender
+      }
+    }, fee, memo, _funds);
+  };
+  unblockSender = async ({
+    sender
+  }: {
+    sender: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      unblock_sender: {
+        sender
+      }
+    }, fee, memo, _funds);
+  };
+  rejectBlockedOffers = async ({
+    sender
+  }: {
+    sender: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      reject_blocked_offers: {
+        sender
+      }
+    }, fee, memo, _funds);
+  };
+  updateAdminParams = async ({
+    bundleLimit,
+    maxIncomingOffers,
+    maxOffers
+  }: {
+    bundleLimit?: number;
+    maxIncomingOffers?: number;
+    maxOffers?: number;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      update_admin_params: {
+        bundle_limit: bundleLimit,
+        max_incoming_offers: maxIncomingOffers,
+        max_offers: maxOffers
+      }
+    }, fee, memo, _funds);
+  };
+  proposeAdmin = async ({
+    admin
+  }: {
+    admin: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      propose_admin: {
+        admin
+      }
+    }, fee, memo, _funds);
+  };
+  acceptAdmin = async (fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      accept_admin: {}
+    }, fee, memo, _funds);
+  };
+  rebuildOffers = async ({
+    limit
+  }: {
+    limit?: number;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      rebuild_offers: {
+        limit
+      }
+    }, fee, memo, _funds);
+  };
 }
\ No newline at end of file
@@ -8,17 +8,19 @@ import { Coin } from "@cosmjs/amino";
 import { MsgExecuteContractEncodeObject } from "@cosmjs/cosmwasm-stargate";
 import { MsgExecuteContract } from "cosmjs-types/cosmwasm/wasm/v1/tx";
 import { toUtf8 } from "@cosmjs/encoding";
-import { ExecuteMsg, Timestamp, Uint64, TokenMsg, InstantiateMsg, ExpiryRange, Addr, OfferResponse, Offer, Token, OffersResponse, ParamsResponse, SudoParams, QueryMsg } from "./Pegasus.types";
+import { Addr, AdminResponse, BlockedSendersResponse, CollectionParamsResponse, CollectionParams, ExpiryRange, CollectionStatsResponse, CollectionStats, ConfigResponse, LimitBounds, DepositsResponse, Token, ExecuteMsg, Timestamp, Uint64, Binary, TokenMsg, Cw721ReceiveMsg, FilteredOffersResponse, Offer, HooksResponse, InboxPreferencesResponse, InboxPreferences, InstantiateMsg, OfferHookExecuteMsg, OfferHookMsg, OfferResponse, OfferStatusResponse, OfferStatus, TokenStatus, OffersHealthResponse, OffersResponse, ParamsResponse, SudoParams, QueryMsg, TradeSide, OfferFilterStatus, OfferFilter, ReceiveNftMsg, Decimal, ReputationResponse, TradeHistory, AcceptIssue, SimulateAcceptResponse, StatsResponse, TradeStats, TokenInterestResponse, UserStatsResponse, UserStats } from "./Pegasus.types";
 export interface PegasusMsg {
   contractAddress: string;
   sender: string;
   createOffer: ({
     expiresAt,
+    memo,
     offeredNfts,
     peer,
     wantedNfts
   }: {
     expiresAt?: Timestamp;
+    memo?: string;
     offeredNfts: TokenMsg[];
     peer: string;
     wantedNfts: TokenMsg[];
@@ -38,11 +40,80 @@ export interface PegasusMsg {
   }: {
     id: number;
   }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  receiveNft: ({
+    msg,
+    sender,
+    tokenId
+  }: {
+    msg: Binary;
+    sender: string;
+    tokenId: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  createEscrowedOffer: ({
+    expiresAt,
+    memo,
+    offeredNfts,
+    peer,
+    wantedNfts
+  }: {
+    expiresAt?: Timestamp;
+    memo?: string;
+    offeredNfts: TokenMsg[];
+    peer: string;
+    wantedNfts: TokenMsg[];
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  withdrawDeposits: ({
+    nfts
+  }: {
+    nfts: TokenMsg[];
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
   removeStaleOffer: ({
     id
   }: {
     id: number;
   }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  setInboxPreferences: ({
+    allowedSenders,
+    minOfferedNfts
+  }: {
+    allowedSenders?: string[];
+    minOfferedNfts: number;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  blockSender: ({
+    sender
+  }: {
+    sender: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  unblockSender: ({
+    sender
+  }: {
+    sender: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  rejectBlockedOffers: ({
+    sender
+  }: {
+    sender: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  updateAdminParams: ({
+    bundleLimit,
+    maxIncomingOffers,
+    maxOffers
+  }: {
+    bundleLimit?: number;
+    maxIncomingOffers?: number;
+    maxOffers?: number;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  proposeAdmin: ({
+    admin
+  }: {
+    admin: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  acceptAdmin: (_funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  rebuildOffers: ({
+    limit
+  }: {
+    limit?: number;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
 }
 export class PegasusMsgComposer implements PegasusMsg {
   sender: string;
@@ -54,15 +125,28 @@ export class PegasusMsgComposer implements PegasusMsg {
     this.removeOffer = this.removeOffer.bind(this);
     this.acceptOffer = this.acceptOffer.bind(this);
     this.rejectOffer = this.rejectOffer.bind(this);
+    this.receiveNft = this.receiveNft.bind(this);
+    this.createEscrowedOffer = this.createEscrowedOffer.bind(this);
+    this.withdrawDeposits = this.withdrawDeposits.bind(this);
     this.removeStaleOffer = this.removeStaleOffer.bind(this);
+    this.setInboxPreferences = this.setInboxPreferences.bind(this);
+    this.blockSender = this.blockSender.bind(this);
+    this.unblockSender = this.unblockSender.bind(this);
+    this.rejectBlockedOffers = this.rejectBlockedOffers.bind(this);
+    this.updateAdminParams = this.updateAdminParams.bind(this);
+    this.proposeAdmin = this.proposeAdmin.bind(this);
+    this.acceptAdmin = this.acceptAdmin.bind(this);
+    this.rebuildOffers = this.rebuildOffers.bind(this);
   }
   createOffer = ({
     expiresAt,
+    memo,
     offeredNfts,
     peer,
     wantedNfts
   }: {
     expiresAt?: Timestamp;
+    memo?: string;
     offeredNfts: TokenMsg[];
     peer: string;
     wantedNfts: TokenMsg[];
@@ -75,6 +159,7 @@ export class PegasusMsgComposer implements PegasusMsg {
         msg: toUtf8(JSON.stringify({
           create_offer: {
             expires_at: expiresAt,
+            memo,
             offered_nfts: offeredNfts,
             peer,
             wanted_nfts: wantedNfts
@@ -141,6 +226,81 @@ export class PegasusMsgComposer implements PegasusMsg {
       })
     };
   };
+  receiveNft = ({
+    msg,
+    sender,
+    tokenId
+  }: {
+    msg: Binary;
+    sender: string;
+    tokenId: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          receive_nft: {
+            msg,
+            sender,
+            token_id: tokenId
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  createEscrowedOffer = ({
+    expiresAt,
+    memo,
+    offeredNfts,
+    peer,
+    wantedNfts
+  }: {
+    expiresAt?: Timestamp;
+    memo?: string;
+    offeredNfts: TokenMsg[];
+    peer: string;
+    wantedNfts: TokenMsg[];
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          create_escrowed_offer: {
+            expires_at: expiresAt,
+            memo,
+            offered_nfts: offeredNfts,
+            peer,
+            wanted_nfts: wantedNfts
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  withdrawDeposits = ({
+    nfts
+  }: {
+    nfts: TokenMsg[];
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          withdraw_deposits: {
+            nfts
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
   removeStaleOffer = ({
     id
   }: {
@@ -160,4 +320,159 @@ export class PegasusMsgComposer implements PegasusMsg {
       })
     };
   };
+  setInboxPreferences = ({
+    allowedSenders,
+    minOfferedNfts
+  }: {
+    allowedSenders?: string[];
+    minOfferedNfts: number;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          set_inbox_preferences: {
+            allowed_senders: allowedSenders,
+            min_offered_nfts: minOfferedNfts
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  blockSender = ({
+    sender
+  }: {
+    sender: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          block_sender: {
+            sender
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  unblockSender = ({
+    sender
+  }: {
+    sender: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          unblock_sender: {
+            sender
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  rejectBlockedOffers = ({
+    sender
+  }: {
+    sender: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          reject_blocked_offers: {
+            sender
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  updateAdminParams = ({
+    bundleLimit,
+    maxIncomingOffers,
+    maxOffers
+  }: {
+    bundleLimit?: number;
+    maxIncomingOffers?: number;
+    maxOffers?: number;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          update_admin_params: {
+            bundle_limit: bundleLimit,
+            max_incoming_offers: maxIncomingOffers,
+            max_offers: maxOffers
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  proposeAdmin = ({
+    admin
+  }: {
+    admin: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          propose_admin: {
+            admin
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  acceptAdmin = (_funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          accept_admin: {}
+        })),
+        funds: _funds
+      })
+    };
+  };
+  rebuildOffers = ({
+    limit
+  }: {
+    limit?: number;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          rebuild_offers: {
+            limit
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
 }
\ No newline at end of file
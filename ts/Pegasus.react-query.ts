@@ -5,7 +5,7 @@
 */
 
 import { UseQueryOptions, useQuery } from "@tanstack/react-query";
-import { ExecuteMsg, Timestamp, Uint64, TokenMsg, InstantiateMsg, ExpiryRange, Addr, OfferResponse, Offer, Token, OffersResponse, ParamsResponse, SudoParams, QueryMsg } from "./Pegasus.types";
+import { Addr, AdminResponse, BlockedSendersResponse, CollectionParamsResponse, CollectionParams, ExpiryRange, CollectionStatsResponse, CollectionStats, ConfigResponse, LimitBounds, DepositsResponse, Token, ExecuteMsg, Timestamp, Uint64, Binary, TokenMsg, Cw721ReceiveMsg, FilteredOffersResponse, Offer, HooksResponse, InboxPreferencesResponse, InboxPreferences, InstantiateMsg, OfferHookExecuteMsg, OfferHookMsg, OfferResponse, OfferStatusResponse, OfferStatus, TokenStatus, OffersHealthResponse, OffersResponse, ParamsResponse, SudoParams, QueryMsg, TradeSide, OfferFilterStatus, OfferFilter, ReceiveNftMsg, Decimal, ReputationResponse, TradeHistory, AcceptIssue, SimulateAcceptResponse, StatsResponse, TradeStats, TokenInterestResponse, UserStatsResponse, UserStats } from "./Pegasus.types";
 import { PegasusQueryClient } from "./Pegasus.client";
 export const pegasusQueryKeys = {
   contract: ([{
@@ -30,6 +30,96 @@ export const pegasusQueryKeys = {
     method: "offers_by_peer",
     args
   }] as const),
+  userStats: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "user_stats",
+    args
+  }] as const),
+  stats: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "stats",
+    args
+  }] as const),
+  collectionStats: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "collection_stats",
+    args
+  }] as const),
+  reputation: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "reputation",
+    args
+  }] as const),
+  tokenInterest: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "token_interest",
+    args
+  }] as const),
+  fillableOffers: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "fillable_offers",
+    args
+  }] as const),
+  inboxPreferences: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "inbox_preferences",
+    args
+  }] as const),
+  deposits: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "deposits",
+    args
+  }] as const),
+  blockedSenders: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "blocked_senders",
+    args
+  }] as const),
+  offerHooks: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "offer_hooks",
+    args
+  }] as const),
+  admin: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "admin",
+    args
+  }] as const),
+  simulateAccept: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "simulate_accept",
+    args
+  }] as const),
+  offerStatus: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "offer_status",
+    args
+  }] as const),
+  offersHealth: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "offers_health",
+    args
+  }] as const),
+  offersByCollection: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "offers_by_collection",
+    args
+  }] as const),
+  offers: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "offers",
+    args
+  }] as const),
+  config: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "config",
+    args
+  }] as const),
+  collectionParams: (contractAddress: string, args?: Record<string, unknown>) => ([{
+    ...pegasusQueryKeys.address(contractAddress)[0],
+    method: "collection_params",
+    args
+  }] as const),
   params: (contractAddress: string, args?: Record<string, unknown>) => ([{
     ...pegasusQueryKeys.address(contractAddress)[0],
     method: "params",
@@ -49,6 +139,256 @@ export function usePegasusParamsQuery<TData = ParamsResponse>({
 }: PegasusParamsQuery<TData>) {
   return useQuery<ParamsResponse, Error, TData>(pegasusQueryKeys.params(client.contractAddress), () => client.params(), options);
 }
+export interface PegasusCollectionParamsQuery<TData> extends PegasusReactQuery<CollectionParamsResponse, TData> {
+  args: {
+    collection: string;
+  };
+}
+export function usePegasusCollectionParamsQuery<TData = CollectionParamsResponse>({
+  client,
+  args,
+  options
+}: PegasusCollectionParamsQuery<TData>) {
+  return useQuery<CollectionParamsResponse, Error, TData>(pegasusQueryKeys.collectionParams(client.contractAddress, args), () => client.collectionParams({
+    collection: args.collection
+  }), options);
+}
+export interface PegasusConfigQuery<TData> extends PegasusReactQuery<ConfigResponse, TData> {}
+export function usePegasusConfigQuery<TData = ConfigResponse>({
+  client,
+  options
+}: PegasusConfigQuery<TData>) {
+  return useQuery<ConfigResponse, Error, TData>(pegasusQueryKeys.config(client.contractAddress), () => client.config(), options);
+}
+export interface PegasusOffersQuery<TData> extends PegasusReactQuery<OffersResponse, TData> {
+  args: {
+    filter: OfferFilter;
+    limit?: number;
+    startAfter?: number;
+  };
+}
+export function usePegasusOffersQuery<TData = OffersResponse>({
+  client,
+  args,
+  options
+}: PegasusOffersQuery<TData>) {
+  return useQuery<OffersResponse, Error, TData>(pegasusQueryKeys.offers(client.contractAddress, args), () => client.offers({
+    filter: args.filter,
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface PegasusOffersByCollectionQuery<TData> extends PegasusReactQuery<OffersByCollectionResponse, TData> {
+  args: {
+    collection: string;
+    limit?: number;
+    side: TradeSide;
+    startAfter?: number;
+  };
+}
+export function usePegasusOffersByCollectionQuery<TData = OffersByCollectionResponse>({
+  client,
+  args,
+  options
+}: PegasusOffersByCollectionQuery<TData>) {
+  return useQuery<OffersByCollectionResponse, Error, TData>(pegasusQueryKeys.offersByCollection(client.contractAddress, args), () => client.offersByCollection({
+    collection: args.collection,
+    limit: args.limit,
+    side: args.side,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface PegasusOffersHealthQuery<TData> extends PegasusReactQuery<OffersHealthResponse, TData> {
+  args: {
+    limit?: number;
+    startAfter?: number;
+  };
+}
+export function usePegasusOffersHealthQuery<TData = OffersHealthResponse>({
+  client,
+  args,
+  options
+}: PegasusOffersHealthQuery<TData>) {
+  return useQuery<OffersHealthResponse, Error, TData>(pegasusQueryKeys.offersHealth(client.contractAddress, args), () => client.offersHealth({
+    limit: args.limit,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface PegasusOfferStatusQuery<TData> extends PegasusReactQuery<OfferStatusResponse, TData> {
+  args: {
+    id: number;
+  };
+}
+export function usePegasusOfferStatusQuery<TData = OfferStatusResponse>({
+  client,
+  args,
+  options
+}: PegasusOfferStatusQuery<TData>) {
+  return useQuery<OfferStatusResponse, Error, TData>(pegasusQueryKeys.offerStatus(client.contractAddress, args), () => client.offerStatus({
+    id: args.id
+  }), options);
+}
+export interface PegasusSimulateAcceptQuery<TData> extends PegasusReactQuery<SimulateAcceptResponse, TData> {
+  args: {
+    accepter: string;
+    id: number;
+  };
+}
+export function usePegasusSimulateAcceptQuery<TData = SimulateAcceptResponse>({
+  client,
+  args,
+  options
+}: PegasusSimulateAcceptQuery<TData>) {
+  return useQuery<SimulateAcceptResponse, Error, TData>(pegasusQueryKeys.simulateAccept(client.contractAddress, args), () => client.simulateAccept({
+    accepter: args.accepter,
+    id: args.id
+  }), options);
+}
+export interface PegasusAdminQuery<TData> extends PegasusReactQuery<AdminResponse, TData> {}
+export function usePegasusAdminQuery<TData = AdminResponse>({
+  client,
+  options
+}: PegasusAdminQuery<TData>) {
+  return useQuery<AdminResponse, Error, TData>(pegasusQueryKeys.admin(client.contractAddress), () => client.admin(), options);
+}
+export interface PegasusOfferHooksQuery<TData> extends PegasusReactQuery<OfferHooksResponse, TData> {}
+export function usePegasusOfferHooksQuery<TData = OfferHooksResponse>({
+  client,
+  options
+}: PegasusOfferHooksQuery<TData>) {
+  return useQuery<OfferHooksResponse, Error, TData>(pegasusQueryKeys.offerHooks(client.contractAddress), () => client.offerHooks(), options);
+}
+export interface PegasusBlockedSendersQuery<TData> extends PegasusReactQuery<BlockedSendersResponse, TData> {
+  args: {
+    owner: string;
+  };
+}
+export function usePegasusBlockedSendersQuery<TData = BlockedSendersResponse>({
+  client,
+  args,
+  options
+}: PegasusBlockedSendersQuery<TData>) {
+  return useQuery<BlockedSendersResponse, Error, TData>(pegasusQueryKeys.blockedSenders(client.contractAddress, args), () => client.blockedSenders({
+    owner: args.owner
+  }), options);
+}
+export interface PegasusDepositsQuery<TData> extends PegasusReactQuery<DepositsResponse, TData> {
+  args: {
+    limit?: number;
+    owner: string;
+    startAfter?: TokenMsg;
+  };
+}
+export function usePegasusDepositsQuery<TData = DepositsResponse>({
+  client,
+  args,
+  options
+}: PegasusDepositsQuery<TData>) {
+  return useQuery<DepositsResponse, Error, TData>(pegasusQueryKeys.deposits(client.contractAddress, args), () => client.deposits({
+    limit: args.limit,
+    owner: args.owner,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface PegasusInboxPreferencesQuery<TData> extends PegasusReactQuery<InboxPreferencesResponse, TData> {
+  args: {
+    address: string;
+  };
+}
+export function usePegasusInboxPreferencesQuery<TData = InboxPreferencesResponse>({
+  client,
+  args,
+  options
+}: PegasusInboxPreferencesQuery<TData>) {
+  return useQuery<InboxPreferencesResponse, Error, TData>(pegasusQueryKeys.inboxPreferences(client.contractAddress, args), () => client.inboxPreferences({
+    address: args.address
+  }), options);
+}
+export interface PegasusFillableOffersQuery<TData> extends PegasusReactQuery<FillableOffersResponse, TData> {
+  args: {
+    collection: string;
+    limit?: number;
+    owner: string;
+    startAfter?: number;
+  };
+}
+export function usePegasusFillableOffersQuery<TData = FillableOffersResponse>({
+  client,
+  args,
+  options
+}: PegasusFillableOffersQuery<TData>) {
+  return useQuery<FillableOffersResponse, Error, TData>(pegasusQueryKeys.fillableOffers(client.contractAddress, args), () => client.fillableOffers({
+    collection: args.collection,
+    limit: args.limit,
+    owner: args.owner,
+    startAfter: args.startAfter
+  }), options);
+}
+export interface PegasusTokenInterestQuery<TData> extends PegasusReactQuery<TokenInterestResponse, TData> {
+  args: {
+    collection: string;
+    tokenId: number;
+  };
+}
+export function usePegasusTokenInterestQuery<TData = TokenInterestResponse>({
+  client,
+  args,
+  options
+}: PegasusTokenInterestQuery<TData>) {
+  return useQuery<TokenInterestResponse, Error, TData>(pegasusQueryKeys.tokenInterest(client.contractAddress, args), () => client.tokenInterest({
+    collection: args.collection,
+    tokenId: args.tokenId
+  }), options);
+}
+export interface PegasusReputationQuery<TData> extends PegasusReactQuery<ReputationResponse, TData> {
+  args: {
+    address: string;
+  };
+}
+export function usePegasusReputationQuery<TData = ReputationResponse>({
+  client,
+  args,
+  options
+}: PegasusReputationQuery<TData>) {
+  return useQuery<ReputationResponse, Error, TData>(pegasusQueryKeys.reputation(client.contractAddress, args), () => client.reputation({
+    address: args.address
+  }), options);
+}
+export interface PegasusCollectionStatsQuery<TData> extends PegasusReactQuery<CollectionStatsResponse, TData> {
+  args: {
+    collection: string;
+  };
+}
+export function usePegasusCollectionStatsQuery<TData = CollectionStatsResponse>({
+  client,
+  args,
+  options
+}: PegasusCollectionStatsQuery<TData>) {
+  return useQuery<CollectionStatsResponse, Error, TData>(pegasusQueryKeys.collectionStats(client.contractAddress, args), () => client.collectionStats({
+    collection: args.collection
+  }), options);
+}
+export interface PegasusStatsQuery<TData> extends PegasusReactQuery<StatsResponse, TData> {}
+export function usePegasusStatsQuery<TData = StatsResponse>({
+  client,
+  options
+}: PegasusStatsQuery<TData>) {
+  return useQuery<StatsResponse, Error, TData>(pegasusQueryKeys.stats(client.contractAddress), () => client.stats(), options);
+}
+export interface PegasusUserStatsQuery<TData> extends PegasusReactQuery<UserStatsResponse, TData> {
+  args: {
+    address: string;
+  };
+}
+export function usePegasusUserStatsQuery<TData = UserStatsResponse>({
+  client,
+  args,
+  options
+}: PegasusUserStatsQuery<TData>) {
+  return useQuery<UserStatsResponse, Error, TData>(pegasusQueryKeys.userStats(client.contractAddress, args), () => client.userStats({
+    address: args.address
+  }), options);
+}
 export interface PegasusOffersByPeerQuery<TData> extends PegasusReactQuery<OffersByPeerResponse, TData> {
   args: {
     peer: string;
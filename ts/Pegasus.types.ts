@@ -4,9 +4,70 @@
 * and run the @cosmwasm/ts-codegen generate command to regenerate this file.
 */
 
+export type Addr = string;
+export interface AdminResponse {
+  admin?: Addr | null;
+  pending_admin?: Addr | null;
+  [k: string]: unknown;
+}
+export interface BlockedSendersResponse {
+  senders: Addr[];
+  [k: string]: unknown;
+}
+export interface CollectionParamsResponse {
+  params: CollectionParams;
+  [k: string]: unknown;
+}
+export interface CollectionParams {
+  max_tokens?: number | null;
+  offer_expiry?: ExpiryRange | null;
+  [k: string]: unknown;
+}
+export interface ExpiryRange {
+  max: number;
+  min: number;
+  [k: string]: unknown;
+}
+export interface CollectionStatsResponse {
+  stats: CollectionStats;
+  [k: string]: unknown;
+}
+export interface CollectionStats {
+  tokens_traded: number;
+  trades: number;
+  [k: string]: unknown;
+}
+export interface ConfigResponse {
+  bundle_limit: number;
+  bundle_limit_bounds: LimitBounds;
+  max_incoming_offers: number;
+  max_incoming_offers_bounds: LimitBounds;
+  max_memo_length: number;
+  max_memo_length_bounds: LimitBounds;
+  max_offers: number;
+  max_offers_bounds: LimitBounds;
+  offer_expiry: ExpiryRange;
+  offer_expiry_bounds: ExpiryRange;
+  [k: string]: unknown;
+}
+export interface LimitBounds {
+  max: number;
+  min: number;
+  [k: string]: unknown;
+}
+export interface DepositsResponse {
+  deposits: Token[];
+  [k: string]: unknown;
+}
+export interface Token {
+  collection: Addr;
+  token_id: number;
+  [k: string]: unknown;
+}
 export type ExecuteMsg = {
   create_offer: {
     expires_at?: Timestamp | null;
+    memo?: string | null;
     offered_nfts: TokenMsg[];
     peer: string;
     wanted_nfts: TokenMsg[];
@@ -27,49 +88,162 @@ export type ExecuteMsg = {
     id: number;
     [k: string]: unknown;
   };
+} | {
+  receive_nft: Cw721ReceiveMsg;
+} | {
+  create_escrowed_offer: {
+    expires_at?: Timestamp | null;
+    memo?: string | null;
+    offered_nfts: TokenMsg[];
+    peer: string;
+    wanted_nfts: TokenMsg[];
+    [k: string]: unknown;
+  };
+} | {
+  withdraw_deposits: {
+    nfts: TokenMsg[];
+    [k: string]: unknown;
+  };
 } | {
   remove_stale_offer: {
     id: number;
     [k: string]: unknown;
   };
+} | {
+  set_inbox_preferences: {
+    allowed_senders?: string[] | null;
+    min_offered_nfts: number;
+    [k: string]: unknown;
+  };
+} | {
+  block_sender: {
+    sender: string;
+    [k: string]: unknown;
+  };
+} | {
+  unblock_sender: {
+    sender: string;
+    [k: string]: unknown;
+  };
+} | {
+  reject_blocked_offers: {
+    sender: string;
+    [k: string]: unknown;
+  };
+} | {
+  update_admin_params: {
+    bundle_limit?: number | null;
+    max_incoming_offers?: number | null;
+    max_offers?: number | null;
+    [k: string]: unknown;
+  };
+} | {
+  propose_admin: {
+    admin: string;
+    [k: string]: unknown;
+  };
+} | {
+  accept_admin: {
+    [k: string]: unknown;
+  };
+} | {
+  rebuild_offers: {
+    limit?: number | null;
+    [k: string]: unknown;
+  };
 };
 export type Timestamp = Uint64;
 export type Uint64 = string;
+export type Binary = string;
 export interface TokenMsg {
   collection: string;
   token_id: number;
   [k: string]: unknown;
 }
+export interface Cw721ReceiveMsg {
+  msg: Binary;
+  sender: string;
+  token_id: string;
+  [k: string]: unknown;
+}
+export interface FilteredOffersResponse {
+  last_scanned?: number | null;
+  offers: Offer[];
+  [k: string]: unknown;
+}
+export interface Offer {
+  created_at: Timestamp;
+  escrowed?: boolean;
+  expires_at: Timestamp;
+  id: number;
+  memo?: string | null;
+  offered_nfts: Token[];
+  peer: Addr;
+  sender: Addr;
+  wanted_nfts: Token[];
+  [k: string]: unknown;
+}
+export interface HooksResponse {
+  hooks: string[];
+  [k: string]: unknown;
+}
+export interface InboxPreferencesResponse {
+  preferences: InboxPreferences;
+  [k: string]: unknown;
+}
+export interface InboxPreferences {
+  allowed_senders?: Addr[] | null;
+  min_offered_nfts: number;
+  [k: string]: unknown;
+}
 export interface InstantiateMsg {
+  admin?: string | null;
   bundle_limit: number;
   maintainer: string;
+  max_incoming_offers: number;
+  max_memo_length: number;
   max_offers: number;
   offer_expiry: ExpiryRange;
   [k: string]: unknown;
 }
-export interface ExpiryRange {
-  max: number;
-  min: number;
+export type OfferHookExecuteMsg = {
+  offer_created_hook: OfferHookMsg;
+} | {
+  offer_accepted_hook: OfferHookMsg;
+} | {
+  offer_rejected_hook: OfferHookMsg;
+} | {
+  offer_cancelled_hook: OfferHookMsg;
+};
+export interface OfferHookMsg {
+  offer: Offer;
   [k: string]: unknown;
 }
-export type Addr = string;
 export interface OfferResponse {
   offer?: Offer | null;
   [k: string]: unknown;
 }
-export interface Offer {
-  created_at: Timestamp;
-  expires_at: Timestamp;
+export interface OfferStatusResponse {
+  status: OfferStatus;
+  [k: string]: unknown;
+}
+export interface OfferStatus {
+  expired: boolean;
   id: number;
-  offered_nfts: Token[];
-  peer: Addr;
-  sender: Addr;
-  wanted_nfts: Token[];
+  offered_nfts: TokenStatus[];
+  valid: boolean;
+  wanted_nfts: TokenStatus[];
   [k: string]: unknown;
 }
-export interface Token {
-  collection: Addr;
-  token_id: number;
+export interface TokenStatus {
+  approved: boolean;
+  owned: boolean;
+  owner?: string | null;
+  token: Token;
+  [k: string]: unknown;
+}
+export interface OffersHealthResponse {
+  offers: OfferStatus[];
   [k: string]: unknown;
 }
 export interface OffersResponse {
@@ -83,6 +257,8 @@ export interface ParamsResponse {
 export interface SudoParams {
   bundle_limit: number;
   maintainer: Addr;
+  max_incoming_offers?: number;
+  max_memo_length?: number;
   max_offers: number;
   offer_expiry: ExpiryRange;
   [k: string]: unknown;
@@ -102,8 +278,200 @@ export type QueryMsg = {
     peer: string;
     [k: string]: unknown;
   };
+} | {
+  user_stats: {
+    address: string;
+    [k: string]: unknown;
+  };
+} | {
+  stats: {
+    [k: string]: unknown;
+  };
+} | {
+  collection_stats: {
+    collection: string;
+    [k: string]: unknown;
+  };
+} | {
+  reputation: {
+    address: string;
+    [k: string]: unknown;
+  };
+} | {
+  token_interest: {
+    collection: string;
+    token_id: number;
+    [k: string]: unknown;
+  };
+} | {
+  fillable_offers: {
+    collection: string;
+    limit?: number | null;
+    owner: string;
+    start_after?: number | null;
+    [k: string]: unknown;
+  };
+} | {
+  inbox_preferences: {
+    address: string;
+    [k: string]: unknown;
+  };
+} | {
+  deposits: {
+    limit?: number | null;
+    owner: string;
+    start_after?: TokenMsg | null;
+    [k: string]: unknown;
+  };
+} | {
+  blocked_senders: {
+    owner: string;
+    [k: string]: unknown;
+  };
+} | {
+  offer_hooks: {
+    [k: string]: unknown;
+  };
+} | {
+  admin: {
+    [k: string]: unknown;
+  };
+} | {
+  simulate_accept: {
+    accepter: string;
+    id: number;
+    [k: string]: unknown;
+  };
+} | {
+  offer_status: {
+    id: number;
+    [k: string]: unknown;
+  };
+} | {
+  offers_health: {
+    limit?: number | null;
+    start_after?: number | null;
+    [k: string]: unknown;
+  };
+} | {
+  offers_by_collection: {
+    collection: string;
+    limit?: number | null;
+    side: TradeSide;
+    start_after?: number | null;
+    [k: string]: unknown;
+  };
+} | {
+  offers: {
+    filter: OfferFilter;
+    limit?: number | null;
+    start_after?: number | null;
+    [k: string]: unknown;
+  };
+} | {
+  config: {
+    [k: string]: unknown;
+  };
+} | {
+  collection_params: {
+    collection: string;
+    [k: string]: unknown;
+  };
 } | {
   params: {
     [k: string]: unknown;
   };
-};
\ No newline at end of file
+};
+export type TradeSide = "offered" | "wanted";
+export type OfferFilterStatus = "open" | "expired";
+export interface OfferFilter {
+  collection?: string | null;
+  expires_before?: Timestamp | null;
+  peer?: string | null;
+  sender?: string | null;
+  status?: OfferFilterStatus | null;
+  [k: string]: unknown;
+}
+export type ReceiveNftMsg = {
+  deposit: {
+    [k: string]: unknown;
+  };
+};
+export type Decimal = string;
+export interface ReputationResponse {
+  history: TradeHistory;
+  reputation?: Decimal | null;
+  [k: string]: unknown;
+}
+export interface TradeHistory {
+  offers_cancelled: number;
+  offers_expired: number;
+  rejections_received: number;
+  trades_completed: number;
+  [k: string]: unknown;
+}
+export type AcceptIssue = {
+  not_peer: {
+    peer: Addr;
+    [k: string]: unknown;
+  };
+} | {
+  expired: {
+    expires_at: Timestamp;
+    [k: string]: unknown;
+  };
+} | {
+  wanted_not_owned: {
+    owner?: string | null;
+    token: Token;
+    [k: string]: unknown;
+  };
+} | {
+  wanted_not_approved: {
+    token: Token;
+    [k: string]: unknown;
+  };
+} | {
+  offered_not_owned: {
+    owner?: string | null;
+    token: Token;
+    [k: string]: unknown;
+  };
+} | {
+  offered_not_approved: {
+    token: Token;
+    [k: string]: unknown;
+  };
+};
+export interface SimulateAcceptResponse {
+  issues: AcceptIssue[];
+  [k: string]: unknown;
+}
+export interface StatsResponse {
+  stats: TradeStats;
+  [k: string]: unknown;
+}
+export interface TradeStats {
+  offers_accepted: number;
+  offers_created: number;
+  offers_expired: number;
+  offers_rejected: number;
+  offers_removed: number;
+  [k: string]: unknown;
+}
+export interface TokenInterestResponse {
+  complete: boolean;
+  latest_offers: Timestamp[];
+  offered_collections: Addr[];
+  open_offers: number;
+  [k: string]: unknown;
+}
+export interface UserStatsResponse {
+  stats: UserStats;
+  [k: string]: unknown;
+}
+export interface UserStats {
+  offers_received: number;
+  offers_sent: number;
+  [k: string]: unknown;
+}
\ No newline at end of file